@@ -0,0 +1,14 @@
+fn main() {
+    // C header is generated to OUT_DIR on every build with capi feature; checked in
+    // include/mahjong_seatings.h is compared with it by capi tests
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        cbindgen::generate(&crate_dir)
+            .expect("Unable to generate C header")
+            .write_to_file(format!("{out_dir}/mahjong_seatings.h"));
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
@@ -0,0 +1,199 @@
+/* Generated by cbindgen from src/capi.rs with capi feature, do not edit manually */
+
+#ifndef MAHJONG_SEATINGS_H
+#define MAHJONG_SEATINGS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Function succeeded
+#define MS_OK 0
+
+// Required pointer argument is null
+#define MS_ERROR_NULL_POINTER 1
+
+// Players count is not a multiple of 4, or ids are not unique, or some id is 0
+#define MS_ERROR_INVALID_PLAYERS 2
+
+// Previous seatings length is not a multiple of 4
+#define MS_ERROR_INVALID_PREVIOUS_SEATINGS 3
+
+// Wind policy, groups count or step is out of range
+#define MS_ERROR_INVALID_ARGUMENT 4
+
+// Unexpected internal error. Reported only by builds where panics unwind (dev and
+// release-capi profiles); library built with release profile aborts the process instead.
+#define MS_ERROR_INTERNAL 5
+
+// Team has more players than tables, so its players can't be seated at different tables
+#define MS_ERROR_TEAM_TOO_LARGE 6
+
+// Wind policies, see WindPolicy
+#define MS_WIND_POLICY_RANDOM 0
+
+#define MS_WIND_POLICY_BALANCED_BY_HISTORY 1
+
+#define MS_WIND_POLICY_BY_RATING 2
+
+#define MS_WIND_POLICY_FIXED 3
+
+// Kinds of problems reported by ms_validate, see Diagnostic
+#define MS_DIAGNOSTIC_PLAYERS_COUNT 1
+
+#define MS_DIAGNOSTIC_INVALID_PLAYER_ID 2
+
+#define MS_DIAGNOSTIC_DUPLICATE_PLAYER 3
+
+#define MS_DIAGNOSTIC_MALFORMED_TABLE 4
+
+#define MS_DIAGNOSTIC_UNKNOWN_PLAYER 5
+
+#define MS_DIAGNOSTIC_SELF_PAIRING 6
+
+#define MS_DIAGNOSTIC_INVALID_PARAMETER 7
+
+// Seating returned to caller: ids and ratings of players, 4 players per table.
+// Should be released with ms_seating_free.
+typedef struct MsSeating {
+  uint32_t *ids;
+  int32_t *ratings;
+  size_t len;
+} MsSeating;
+
+// Intersections table returned to caller: (player1, player2, games played together) triples
+// in flat array of len * 3 items. Should be released with ms_intersections_free.
+typedef struct MsIntersections {
+  uint32_t *data;
+  size_t len;
+} MsIntersections;
+
+// Problem of input found by ms_validate: kind is one of MS_DIAGNOSTIC_* constants, table is
+// index of previous table (SIZE_MAX for problems of players list), id is player id and size is
+// players count or table size (0 if not applicable). Seating functions reject input with fatal
+// problems, while unknown and repeated players in previous seatings are accepted.
+typedef struct MsDiagnostic {
+  uint32_t kind;
+  size_t table;
+  uint32_t id;
+  size_t size;
+  bool fatal;
+} MsDiagnostic;
+
+// Problems returned to caller. Should be released with ms_diagnostics_free.
+typedef struct MsDiagnostics {
+  struct MsDiagnostic *data;
+  size_t len;
+} MsDiagnostics;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Swiss seating, see make_swiss_seating.
+// $previous_seatings is a flat array of players ids, 4 per table.
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_make_swiss_seating(const uint32_t *ids,
+                              const int32_t *ratings,
+                              size_t players_count,
+                              const uint32_t *previous_seatings,
+                              size_t previous_seatings_len,
+                              uint32_t wind_policy,
+                              uint64_t rand_factor,
+                              struct MsSeating *out);
+
+// Shuffled seating, see make_shuffled_seating.
+// $previous_seatings is a flat array of players ids, 4 per table.
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_make_shuffled_seating(const uint32_t *ids,
+                                 const int32_t *ratings,
+                                 size_t players_count,
+                                 const uint32_t *previous_seatings,
+                                 size_t previous_seatings_len,
+                                 uint32_t groups_count,
+                                 uint32_t wind_policy,
+                                 uint64_t rand_factor,
+                                 struct MsSeating *out);
+
+// Team seating, see make_team_seating.
+// $teams is a flat array of (player id, team id) pairs, $teams_len is count of its items;
+// $previous_seatings is a flat array of players ids, 4 per table.
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_make_team_seating(const uint32_t *ids,
+                             const int32_t *ratings,
+                             size_t players_count,
+                             const uint32_t *teams,
+                             size_t teams_len,
+                             const uint32_t *previous_seatings,
+                             size_t previous_seatings_len,
+                             uint32_t wind_policy,
+                             uint64_t rand_factor,
+                             struct MsSeating *out);
+
+// Interval seating, see make_interval_seating
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_make_interval_seating(const uint32_t *ids,
+                                 const int32_t *ratings,
+                                 size_t players_count,
+                                 size_t step,
+                                 uint32_t wind_policy,
+                                 uint64_t rand_factor,
+                                 struct MsSeating *out);
+
+// Intersections table of given seating and previous seatings, see make_intersections_table
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_make_intersections_table(const uint32_t *ids,
+                                    const int32_t *ratings,
+                                    size_t players_count,
+                                    const uint32_t *previous_seatings,
+                                    size_t previous_seatings_len,
+                                    struct MsIntersections *out);
+
+// Strict validation of players and previous seatings, see validate.
+// All problems found are written to out, no problems means input is valid.
+// $previous_seatings is a flat array of players ids, 4 per table.
+//
+// # Safety
+// Arrays should be valid for given lengths, out should be a valid pointer.
+int32_t ms_validate(const uint32_t *ids,
+                    const int32_t *ratings,
+                    size_t players_count,
+                    const uint32_t *previous_seatings,
+                    size_t previous_seatings_len,
+                    struct MsDiagnostics *out);
+
+// Release diagnostics returned by library; safe to call twice or on empty list.
+//
+// # Safety
+// diagnostics should be null or point to a struct filled by the library.
+void ms_diagnostics_free(struct MsDiagnostics *diagnostics);
+
+// Release seating returned by library; safe to call twice or on empty seating.
+//
+// # Safety
+// seating should be null or point to a struct filled by the library.
+void ms_seating_free(struct MsSeating *seating);
+
+// Release intersections table returned by library; safe to call twice or on empty table.
+//
+// # Safety
+// intersections should be null or point to a struct filled by the library.
+void ms_intersections_free(struct MsIntersections *intersections);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MAHJONG_SEATINGS_H */
@@ -0,0 +1,83 @@
+import unittest
+
+import mahjong_seatings
+
+
+def make_players(count):
+    return [(player_id, 1500 - player_id) for player_id in range(1, count + 1)]
+
+
+class TestSeatings(unittest.TestCase):
+    def test_make_swiss_seating(self):
+        players = make_players(16)
+        seating = mahjong_seatings.make_swiss_seating(players, [], "fixed", 12345)
+        self.assertEqual(sorted(seating), players)
+
+    def test_make_shuffled_seating(self):
+        players = make_players(16)
+        previous = [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]]
+        seating = mahjong_seatings.make_shuffled_seating(players, previous, rand_factor=12345)
+        self.assertEqual(sorted(seating), players)
+
+    def test_make_team_seating(self):
+        players = make_players(16)
+        teams = [(player_id, (player_id - 1) // 4) for player_id in range(1, 17)]
+        seating = mahjong_seatings.make_team_seating(players, teams, [], "fixed", 12345)
+        self.assertEqual(sorted(seating), players)
+        for start in range(0, 16, 4):
+            table_teams = {(player_id - 1) // 4 for player_id, _ in seating[start:start + 4]}
+            self.assertEqual(len(table_teams), 4)
+
+        # Five players of team 0 can't be seated at four tables
+        teams[4] = (5, 0)
+        with self.assertRaises(ValueError) as context:
+            mahjong_seatings.make_team_seating(players, teams, [])
+        self.assertIn("team 0 has 5 players", str(context.exception))
+
+    def test_make_interval_seating(self):
+        players = make_players(16)
+        seating = mahjong_seatings.make_interval_seating(players, 2)
+        self.assertEqual(sorted(seating), players)
+        self.assertEqual(seating, mahjong_seatings.make_interval_seating(players, 2, "random", 0))
+
+    def test_make_intersections_table(self):
+        players = make_players(4)
+        table = mahjong_seatings.make_intersections_table(players, [[1, 2, 3, 4]])
+        self.assertEqual(len(table), 6)
+        self.assertEqual(table[(1, 2)], 2)
+
+    def test_make_quality_report(self):
+        seating = [(1, 1600), (5, 1500), (2, 1550), (6, 1450), (3, 1400), (7, 1300), (4, 1350), (8, 1250)]
+        previous = [[1, 2, 3, 4], [5, 6, 7, 8], [1, 2, 7, 8]]
+        report = mahjong_seatings.make_quality_report(seating, previous)
+        self.assertEqual(report["repeats"], 6)
+        self.assertEqual(report["max_repeats"], 2)
+        self.assertEqual(report["repeated_pairs"], [(1, 2, 2), (5, 6, 1), (3, 4, 1), (7, 8, 2)])
+        self.assertEqual(report["rating_variance"], 10000.0)
+        self.assertEqual(report["max_rating_spread"], 150)
+
+    def test_invalid_input(self):
+        with self.assertRaises(ValueError):
+            mahjong_seatings.make_swiss_seating(make_players(6), [])
+        with self.assertRaises(ValueError):
+            mahjong_seatings.make_swiss_seating([(1, 0), (1, 0), (2, 0), (3, 0)], [])
+        with self.assertRaises(ValueError):
+            mahjong_seatings.make_swiss_seating(make_players(4), [[1, 2, 3]])
+        with self.assertRaises(ValueError):
+            mahjong_seatings.make_shuffled_seating(make_players(4), [], wind_policy="north")
+        with self.assertRaises(ValueError):
+            mahjong_seatings.make_interval_seating(make_players(4), 0)
+
+    def test_strict_validation(self):
+        players = make_players(4)
+        previous = [[1, 2, 3, 42]]
+        # Unknown players in previous seatings are accepted unless strict validation is requested
+        seating = mahjong_seatings.make_swiss_seating(players, previous, "fixed", 12345)
+        self.assertEqual(sorted(seating), players)
+        with self.assertRaises(ValueError) as context:
+            mahjong_seatings.make_swiss_seating(players, previous, "fixed", 12345, strict=True)
+        self.assertIn("unknown player 42", str(context.exception))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,42 @@
+//! HTTP/JSON seating service, build it with release-server profile.
+//! Usage: seating_server [--address 127.0.0.1:8080] [--max-body-bytes 1048576] [--time-budget-ms 5000]
+//!        [--max-computations 4] [--workers 4]
+use mahjong_seatings_rust::server::{run, ServerConfig};
+
+fn parse_args() -> Result<ServerConfig, String> {
+    let mut config = ServerConfig::default();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    for pair in args.chunks(2) {
+        let value = pair
+            .get(1)
+            .ok_or_else(|| format!("missing value for {}", pair[0]))?;
+        let parse_number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid value for {}: {value}", pair[0]))
+        };
+        match pair[0].as_str() {
+            "--address" => config.address = value.clone(),
+            "--max-body-bytes" => config.max_body_bytes = parse_number()?,
+            "--time-budget-ms" => config.time_budget_ms = parse_number()? as u64,
+            "--max-computations" => config.max_computations = parse_number()?,
+            "--workers" => config.workers = parse_number()?,
+            other => return Err(format!("unknown option {other}")),
+        }
+    }
+
+    Ok(config)
+}
+
+fn main() {
+    let result = parse_args().and_then(|config| {
+        println!("Listening on {}", config.address);
+        run(config)
+    });
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
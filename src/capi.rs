@@ -0,0 +1,767 @@
+#![allow(unsafe_code)]
+
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::intersections::make_intersections_table;
+use crate::seating_interval::make_interval_seating;
+use crate::seating_shuffle::make_shuffled_seating;
+use crate::seating_swiss::make_swiss_seating;
+use crate::seating_team::{make_team_seating, TeamError};
+use crate::validation::{validate, Diagnostic};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::ptr;
+
+/// Function succeeded
+pub const MS_OK: i32 = 0;
+/// Required pointer argument is null
+pub const MS_ERROR_NULL_POINTER: i32 = 1;
+/// Players count is not a multiple of 4, or ids are not unique, or some id is 0
+pub const MS_ERROR_INVALID_PLAYERS: i32 = 2;
+/// Previous seatings length is not a multiple of 4
+pub const MS_ERROR_INVALID_PREVIOUS_SEATINGS: i32 = 3;
+/// Wind policy, groups count or step is out of range
+pub const MS_ERROR_INVALID_ARGUMENT: i32 = 4;
+/// Unexpected internal error. Reported only by builds where panics unwind (dev and
+/// release-capi profiles); library built with release profile aborts the process instead.
+pub const MS_ERROR_INTERNAL: i32 = 5;
+/// Team has more players than tables, so its players can't be seated at different tables
+pub const MS_ERROR_TEAM_TOO_LARGE: i32 = 6;
+
+/// Wind policies, see WindPolicy
+pub const MS_WIND_POLICY_RANDOM: u32 = 0;
+pub const MS_WIND_POLICY_BALANCED_BY_HISTORY: u32 = 1;
+pub const MS_WIND_POLICY_BY_RATING: u32 = 2;
+pub const MS_WIND_POLICY_FIXED: u32 = 3;
+
+/// Kinds of problems reported by ms_validate, see Diagnostic
+pub const MS_DIAGNOSTIC_PLAYERS_COUNT: u32 = 1;
+pub const MS_DIAGNOSTIC_INVALID_PLAYER_ID: u32 = 2;
+pub const MS_DIAGNOSTIC_DUPLICATE_PLAYER: u32 = 3;
+pub const MS_DIAGNOSTIC_MALFORMED_TABLE: u32 = 4;
+pub const MS_DIAGNOSTIC_UNKNOWN_PLAYER: u32 = 5;
+pub const MS_DIAGNOSTIC_SELF_PAIRING: u32 = 6;
+pub const MS_DIAGNOSTIC_INVALID_PARAMETER: u32 = 7;
+
+/// Problem of input found by ms_validate: kind is one of MS_DIAGNOSTIC_* constants, table is
+/// index of previous table (SIZE_MAX for problems of players list), id is player id and size is
+/// players count or table size (0 if not applicable). Seating functions reject input with fatal
+/// problems, while unknown and repeated players in previous seatings are accepted.
+#[repr(C)]
+pub struct MsDiagnostic {
+    pub kind: u32,
+    pub table: usize,
+    pub id: u32,
+    pub size: usize,
+    pub fatal: bool,
+}
+
+/// Problems returned to caller. Should be released with ms_diagnostics_free.
+#[repr(C)]
+pub struct MsDiagnostics {
+    pub data: *mut MsDiagnostic,
+    pub len: usize,
+}
+
+/// Seating returned to caller: ids and ratings of players, 4 players per table.
+/// Should be released with ms_seating_free.
+#[repr(C)]
+pub struct MsSeating {
+    pub ids: *mut u32,
+    pub ratings: *mut i32,
+    pub len: usize,
+}
+
+/// Intersections table returned to caller: (player1, player2, games played together) triples
+/// in flat array of len * 3 items. Should be released with ms_intersections_free.
+#[repr(C)]
+pub struct MsIntersections {
+    pub data: *mut u32,
+    pub len: usize,
+}
+
+/// Plain arrays passed by caller
+struct Input {
+    players: PlayersMap,
+    previous_seatings: Vec<Vec<u32>>,
+}
+
+/// Copy caller arrays into players map and previous seatings, validating them.
+/// Unknown and repeated players in previous seatings are accepted, see ms_validate.
+///
+/// # Safety
+/// Pointers should be valid for given lengths; previous_seatings may be null if its length is 0.
+unsafe fn read_input(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+) -> Result<Input, i32> {
+    if ids.is_null() || ratings.is_null() {
+        return Err(MS_ERROR_NULL_POINTER);
+    }
+    if previous_seatings.is_null() && previous_seatings_len > 0 {
+        return Err(MS_ERROR_NULL_POINTER);
+    }
+
+    let ids = std::slice::from_raw_parts(ids, players_count);
+    let ratings = std::slice::from_raw_parts(ratings, players_count);
+    let players: PlayersMap = ids.iter().copied().zip(ratings.iter().copied()).collect();
+
+    if !previous_seatings_len.is_multiple_of(4) {
+        return Err(MS_ERROR_INVALID_PREVIOUS_SEATINGS);
+    }
+    let previous_seatings = if previous_seatings_len == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(previous_seatings, previous_seatings_len)
+            .chunks(4)
+            .map(|table| table.to_vec())
+            .collect()
+    };
+
+    let diagnostics = validate(&players, &previous_seatings);
+    match diagnostics.iter().find(|diagnostic| diagnostic.is_fatal()) {
+        Some(diagnostic) if diagnostic.table().is_none() => Err(MS_ERROR_INVALID_PLAYERS),
+        Some(_) => Err(MS_ERROR_INVALID_PREVIOUS_SEATINGS),
+        None => Ok(Input {
+            players,
+            previous_seatings,
+        }),
+    }
+}
+
+fn parse_wind_policy(wind_policy: u32) -> Result<WindPolicy, i32> {
+    match wind_policy {
+        MS_WIND_POLICY_RANDOM => Ok(WindPolicy::Random),
+        MS_WIND_POLICY_BALANCED_BY_HISTORY => Ok(WindPolicy::BalancedByHistory),
+        MS_WIND_POLICY_BY_RATING => Ok(WindPolicy::ByRating),
+        MS_WIND_POLICY_FIXED => Ok(WindPolicy::Fixed),
+        _ => Err(MS_ERROR_INVALID_ARGUMENT),
+    }
+}
+
+/// Run seating function and write its result to caller's struct
+///
+/// # Safety
+/// out should be a valid pointer
+unsafe fn write_seating<F>(out: *mut MsSeating, make_seating: F) -> i32
+where
+    F: FnOnce() -> Result<PlayersMap, i32> + UnwindSafe,
+{
+    if out.is_null() {
+        return MS_ERROR_NULL_POINTER;
+    }
+    (*out) = MsSeating {
+        ids: ptr::null_mut(),
+        ratings: ptr::null_mut(),
+        len: 0,
+    };
+
+    let seating = match catch_unwind(make_seating) {
+        Ok(Ok(seating)) => seating,
+        Ok(Err(code)) => return code,
+        Err(_) => return MS_ERROR_INTERNAL,
+    };
+
+    let ids: Box<[u32]> = seating.iter().map(|item| item.0).collect();
+    let ratings: Box<[i32]> = seating.iter().map(|item| item.1).collect();
+    (*out) = MsSeating {
+        len: ids.len(),
+        ids: Box::into_raw(ids) as *mut u32,
+        ratings: Box::into_raw(ratings) as *mut i32,
+    };
+    MS_OK
+}
+
+/// Swiss seating, see make_swiss_seating.
+/// $previous_seatings is a flat array of players ids, 4 per table.
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ms_make_swiss_seating(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+    wind_policy: u32,
+    rand_factor: u64,
+    out: *mut MsSeating,
+) -> i32 {
+    write_seating(out, || {
+        let input = read_input(
+            ids,
+            ratings,
+            players_count,
+            previous_seatings,
+            previous_seatings_len,
+        )?;
+        Ok(make_swiss_seating(
+            &input.players,
+            &input.previous_seatings,
+            parse_wind_policy(wind_policy)?,
+            rand_factor,
+        ))
+    })
+}
+
+/// Shuffled seating, see make_shuffled_seating.
+/// $previous_seatings is a flat array of players ids, 4 per table.
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ms_make_shuffled_seating(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+    groups_count: u32,
+    wind_policy: u32,
+    rand_factor: u64,
+    out: *mut MsSeating,
+) -> i32 {
+    write_seating(out, || {
+        let input = read_input(
+            ids,
+            ratings,
+            players_count,
+            previous_seatings,
+            previous_seatings_len,
+        )?;
+        if groups_count == 0 {
+            return Err(MS_ERROR_INVALID_ARGUMENT);
+        }
+        Ok(make_shuffled_seating(
+            &input.players,
+            &input.previous_seatings,
+            groups_count,
+            parse_wind_policy(wind_policy)?,
+            rand_factor,
+        ))
+    })
+}
+
+/// Team seating, see make_team_seating.
+/// $teams is a flat array of (player id, team id) pairs, $teams_len is count of its items;
+/// $previous_seatings is a flat array of players ids, 4 per table.
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ms_make_team_seating(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    teams: *const u32,
+    teams_len: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+    wind_policy: u32,
+    rand_factor: u64,
+    out: *mut MsSeating,
+) -> i32 {
+    write_seating(out, || {
+        let input = read_input(
+            ids,
+            ratings,
+            players_count,
+            previous_seatings,
+            previous_seatings_len,
+        )?;
+        if teams.is_null() && teams_len > 0 {
+            return Err(MS_ERROR_NULL_POINTER);
+        }
+        if !teams_len.is_multiple_of(2) {
+            return Err(MS_ERROR_INVALID_ARGUMENT);
+        }
+        let teams: Vec<(u32, u32)> = if teams_len == 0 {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(teams, teams_len)
+                .chunks(2)
+                .map(|pair| (pair[0], pair[1]))
+                .collect()
+        };
+        make_team_seating(
+            &input.players,
+            &teams,
+            &input.previous_seatings,
+            parse_wind_policy(wind_policy)?,
+            rand_factor,
+        )
+        .map_err(|error| match error {
+            TeamError::PlayersCount { .. } => MS_ERROR_INVALID_PLAYERS,
+            TeamError::TeamTooLarge { .. } => MS_ERROR_TEAM_TOO_LARGE,
+        })
+    })
+}
+
+/// Interval seating, see make_interval_seating
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ms_make_interval_seating(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    step: usize,
+    wind_policy: u32,
+    rand_factor: u64,
+    out: *mut MsSeating,
+) -> i32 {
+    write_seating(out, || {
+        let input = read_input(ids, ratings, players_count, ptr::null(), 0)?;
+        if step == 0 {
+            return Err(MS_ERROR_INVALID_ARGUMENT);
+        }
+        Ok(make_interval_seating(
+            &input.players,
+            step,
+            parse_wind_policy(wind_policy)?,
+            rand_factor,
+        ))
+    })
+}
+
+/// Intersections table of given seating and previous seatings, see make_intersections_table
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ms_make_intersections_table(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+    out: *mut MsIntersections,
+) -> i32 {
+    if out.is_null() {
+        return MS_ERROR_NULL_POINTER;
+    }
+    (*out) = MsIntersections {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+
+    let result = catch_unwind(|| {
+        let input = read_input(
+            ids,
+            ratings,
+            players_count,
+            previous_seatings,
+            previous_seatings_len,
+        )?;
+        Ok(make_intersections_table(
+            &input.players,
+            &input.previous_seatings,
+        ))
+    });
+    let intersections = match result {
+        Ok(Ok(intersections)) => intersections,
+        Ok(Err(code)) => return code,
+        Err(_) => return MS_ERROR_INTERNAL,
+    };
+
+    let data: Box<[u32]> = intersections
+        .iter()
+        .flat_map(|item| [item.0, item.1, item.2])
+        .collect();
+    (*out) = MsIntersections {
+        len: intersections.len(),
+        data: Box::into_raw(data) as *mut u32,
+    };
+    MS_OK
+}
+
+/// Strict validation of players and previous seatings, see validate.
+/// All problems found are written to out, no problems means input is valid.
+/// $previous_seatings is a flat array of players ids, 4 per table.
+///
+/// # Safety
+/// Arrays should be valid for given lengths, out should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ms_validate(
+    ids: *const u32,
+    ratings: *const i32,
+    players_count: usize,
+    previous_seatings: *const u32,
+    previous_seatings_len: usize,
+    out: *mut MsDiagnostics,
+) -> i32 {
+    if out.is_null() {
+        return MS_ERROR_NULL_POINTER;
+    }
+    (*out) = MsDiagnostics {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+    if ids.is_null() || ratings.is_null() {
+        return MS_ERROR_NULL_POINTER;
+    }
+    if previous_seatings.is_null() && previous_seatings_len > 0 {
+        return MS_ERROR_NULL_POINTER;
+    }
+
+    let ids = std::slice::from_raw_parts(ids, players_count);
+    let ratings = std::slice::from_raw_parts(ratings, players_count);
+    let players: PlayersMap = ids.iter().copied().zip(ratings.iter().copied()).collect();
+    // Last table is reported as malformed if length is not a multiple of 4
+    let previous_seatings: Vec<Vec<u32>> = if previous_seatings_len == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(previous_seatings, previous_seatings_len)
+            .chunks(4)
+            .map(|table| table.to_vec())
+            .collect()
+    };
+
+    let data: Box<[MsDiagnostic]> = validate(&players, &previous_seatings)
+        .iter()
+        .map(make_ms_diagnostic)
+        .collect();
+    (*out) = MsDiagnostics {
+        len: data.len(),
+        data: Box::into_raw(data) as *mut MsDiagnostic,
+    };
+    MS_OK
+}
+
+fn make_ms_diagnostic(diagnostic: &Diagnostic) -> MsDiagnostic {
+    let (kind, id, size) = match diagnostic {
+        Diagnostic::PlayersCount { count } => (MS_DIAGNOSTIC_PLAYERS_COUNT, 0, *count),
+        Diagnostic::InvalidPlayerId => (MS_DIAGNOSTIC_INVALID_PLAYER_ID, 0, 0),
+        Diagnostic::DuplicatePlayer { id } => (MS_DIAGNOSTIC_DUPLICATE_PLAYER, *id, 0),
+        Diagnostic::MalformedTable { size, .. } => (MS_DIAGNOSTIC_MALFORMED_TABLE, 0, *size),
+        Diagnostic::UnknownPlayer { id, .. } => (MS_DIAGNOSTIC_UNKNOWN_PLAYER, *id, 0),
+        Diagnostic::SelfPairing { id, .. } => (MS_DIAGNOSTIC_SELF_PAIRING, *id, 0),
+        Diagnostic::InvalidParameter { .. } => (MS_DIAGNOSTIC_INVALID_PARAMETER, 0, 0),
+    };
+    MsDiagnostic {
+        kind,
+        table: diagnostic.table().unwrap_or(usize::MAX),
+        id,
+        size,
+        fatal: diagnostic.is_fatal(),
+    }
+}
+
+/// Release diagnostics returned by library; safe to call twice or on empty list.
+///
+/// # Safety
+/// diagnostics should be null or point to a struct filled by the library.
+#[no_mangle]
+pub unsafe extern "C" fn ms_diagnostics_free(diagnostics: *mut MsDiagnostics) {
+    if diagnostics.is_null() {
+        return;
+    }
+    let diagnostics = &mut *diagnostics;
+    if !diagnostics.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            diagnostics.data,
+            diagnostics.len,
+        )));
+    }
+    diagnostics.data = ptr::null_mut();
+    diagnostics.len = 0;
+}
+
+/// Release seating returned by library; safe to call twice or on empty seating.
+///
+/// # Safety
+/// seating should be null or point to a struct filled by the library.
+#[no_mangle]
+pub unsafe extern "C" fn ms_seating_free(seating: *mut MsSeating) {
+    if seating.is_null() {
+        return;
+    }
+    let seating = &mut *seating;
+    if !seating.ids.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            seating.ids,
+            seating.len,
+        )));
+    }
+    if !seating.ratings.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            seating.ratings,
+            seating.len,
+        )));
+    }
+    seating.ids = ptr::null_mut();
+    seating.ratings = ptr::null_mut();
+    seating.len = 0;
+}
+
+/// Release intersections table returned by library; safe to call twice or on empty table.
+///
+/// # Safety
+/// intersections should be null or point to a struct filled by the library.
+#[no_mangle]
+pub unsafe extern "C" fn ms_intersections_free(intersections: *mut MsIntersections) {
+    if intersections.is_null() {
+        return;
+    }
+    let intersections = &mut *intersections;
+    if !intersections.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            intersections.data,
+            intersections.len * 3,
+        )));
+    }
+    intersections.data = ptr::null_mut();
+    intersections.len = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_seating() -> MsSeating {
+        MsSeating {
+            ids: ptr::null_mut(),
+            ratings: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn test_ms_make_swiss_seating() {
+        let ids: Vec<u32> = (1..=8).collect();
+        let ratings: Vec<i32> = ids.iter().map(|id| 1500 - *id as i32).collect();
+        let previous = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut seating = empty_seating();
+
+        let code = unsafe {
+            ms_make_swiss_seating(
+                ids.as_ptr(),
+                ratings.as_ptr(),
+                ids.len(),
+                previous.as_ptr(),
+                previous.len(),
+                MS_WIND_POLICY_FIXED,
+                12345,
+                &mut seating,
+            )
+        };
+        assert_eq!(code, MS_OK);
+        assert_eq!(seating.len, 8);
+
+        let mut seated = unsafe { std::slice::from_raw_parts(seating.ids, seating.len) }.to_vec();
+        seated.sort();
+        assert_eq!(seated, ids);
+
+        unsafe {
+            ms_seating_free(&mut seating);
+            ms_seating_free(&mut seating);
+        }
+        assert!(seating.ids.is_null());
+    }
+
+    #[test]
+    fn test_ms_make_team_seating() {
+        let ids: Vec<u32> = (1..=8).collect();
+        let ratings: Vec<i32> = ids.iter().map(|id| 1500 - *id as i32).collect();
+        let mut teams = [1, 10, 2, 10, 3, 20, 4, 20];
+        let mut seating = empty_seating();
+
+        let code = unsafe {
+            ms_make_team_seating(
+                ids.as_ptr(),
+                ratings.as_ptr(),
+                ids.len(),
+                teams.as_ptr(),
+                teams.len(),
+                ptr::null(),
+                0,
+                MS_WIND_POLICY_FIXED,
+                12345,
+                &mut seating,
+            )
+        };
+        assert_eq!(code, MS_OK);
+        let seated = unsafe { std::slice::from_raw_parts(seating.ids, seating.len) };
+        for table in seated.chunks(4) {
+            assert!(!(table.contains(&1) && table.contains(&2)));
+            assert!(!(table.contains(&3) && table.contains(&4)));
+        }
+        unsafe { ms_seating_free(&mut seating) };
+
+        // Team 10 of 3 players can't be seated at 2 tables
+        teams[5] = 10;
+        let code = unsafe {
+            ms_make_team_seating(
+                ids.as_ptr(),
+                ratings.as_ptr(),
+                ids.len(),
+                teams.as_ptr(),
+                teams.len(),
+                ptr::null(),
+                0,
+                MS_WIND_POLICY_FIXED,
+                12345,
+                &mut seating,
+            )
+        };
+        assert_eq!(code, MS_ERROR_TEAM_TOO_LARGE);
+        assert!(seating.ids.is_null());
+    }
+
+    #[test]
+    fn test_ms_errors() {
+        let ids = [1, 2, 3, 3];
+        let ratings = [1500, 1500, 1500, 1500];
+        let mut seating = empty_seating();
+
+        unsafe {
+            assert_eq!(
+                ms_make_swiss_seating(
+                    ids.as_ptr(),
+                    ratings.as_ptr(),
+                    4,
+                    ptr::null(),
+                    0,
+                    MS_WIND_POLICY_FIXED,
+                    1,
+                    &mut seating
+                ),
+                MS_ERROR_INVALID_PLAYERS
+            );
+            assert_eq!(
+                ms_make_swiss_seating(
+                    ptr::null(),
+                    ratings.as_ptr(),
+                    4,
+                    ptr::null(),
+                    0,
+                    MS_WIND_POLICY_FIXED,
+                    1,
+                    &mut seating
+                ),
+                MS_ERROR_NULL_POINTER
+            );
+
+            let ids = [1, 2, 3, 4];
+            assert_eq!(
+                ms_make_shuffled_seating(
+                    ids.as_ptr(),
+                    ratings.as_ptr(),
+                    4,
+                    ids.as_ptr(),
+                    3,
+                    1,
+                    MS_WIND_POLICY_FIXED,
+                    1,
+                    &mut seating
+                ),
+                MS_ERROR_INVALID_PREVIOUS_SEATINGS
+            );
+            assert_eq!(
+                ms_make_interval_seating(ids.as_ptr(), ratings.as_ptr(), 4, 1, 10, 1, &mut seating),
+                MS_ERROR_INVALID_ARGUMENT
+            );
+        }
+        assert!(seating.ids.is_null());
+    }
+
+    #[test]
+    fn test_ms_validate() {
+        let ids = [1, 2, 3, 4];
+        let ratings = [1500, 1500, 1500, 1500];
+        let previous = [1, 2, 3, 42, 1, 2];
+        let mut diagnostics = MsDiagnostics {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+        let mut seating = empty_seating();
+
+        unsafe {
+            assert_eq!(
+                ms_validate(
+                    ids.as_ptr(),
+                    ratings.as_ptr(),
+                    4,
+                    previous.as_ptr(),
+                    previous.len(),
+                    &mut diagnostics
+                ),
+                MS_OK
+            );
+            let data = std::slice::from_raw_parts(diagnostics.data, diagnostics.len);
+            let kinds: Vec<(u32, usize, u32, usize, bool)> = data
+                .iter()
+                .map(|item| (item.kind, item.table, item.id, item.size, item.fatal))
+                .collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    (MS_DIAGNOSTIC_UNKNOWN_PLAYER, 0, 42, 0, false),
+                    (MS_DIAGNOSTIC_MALFORMED_TABLE, 1, 0, 2, true),
+                ]
+            );
+            ms_diagnostics_free(&mut diagnostics);
+            ms_diagnostics_free(&mut diagnostics);
+            assert!(diagnostics.data.is_null());
+
+            // Seating functions accept unknown players in previous seatings
+            assert_eq!(
+                ms_make_swiss_seating(
+                    ids.as_ptr(),
+                    ratings.as_ptr(),
+                    4,
+                    previous.as_ptr(),
+                    4,
+                    MS_WIND_POLICY_FIXED,
+                    1,
+                    &mut seating
+                ),
+                MS_OK
+            );
+            ms_seating_free(&mut seating);
+        }
+    }
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/mahjong_seatings.h"));
+        let checked_in = include_str!("../include/mahjong_seatings.h");
+        assert!(
+            generated == checked_in,
+            "include/mahjong_seatings.h is outdated, regenerate it with `make -C tests/c header`"
+        );
+    }
+
+    #[test]
+    fn test_ms_make_intersections_table() {
+        let ids = [1, 2, 3, 4];
+        let ratings = [1500, 1500, 1500, 1500];
+        let mut intersections = MsIntersections {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+
+        unsafe {
+            assert_eq!(
+                ms_make_intersections_table(
+                    ids.as_ptr(),
+                    ratings.as_ptr(),
+                    4,
+                    ids.as_ptr(),
+                    4,
+                    &mut intersections
+                ),
+                MS_OK
+            );
+            assert_eq!(intersections.len, 6);
+            let data = std::slice::from_raw_parts(intersections.data, 18);
+            assert!(data.chunks(3).all(|item| item[2] == 2));
+            ms_intersections_free(&mut intersections);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/// Non-empty lines of text with 1-based line numbers
+pub(crate) fn numbered_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Tab if header row contains tabs (TSV), comma otherwise (CSV)
+pub(crate) fn detect_delimiter(header: &str) -> char {
+    if header.contains('\t') {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// Split delimited line, fields may be quoted with double quotes ("" is an escaped quote).
+/// Returns None if quoted field is not terminated.
+pub(crate) fn split_fields(line: &str, delimiter: char) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (c, false) if c == delimiter => {
+                fields.push(std::mem::take(&mut field).trim().to_string())
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field.trim().to_string());
+
+    Some(fields)
+}
+
+/// Position of each of $columns in header row (case insensitive), None for missing columns
+pub(crate) fn find_columns(header: &[String], columns: &[&str]) -> Vec<Option<usize>> {
+    columns
+        .iter()
+        .map(|column| {
+            header
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(column))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fields() {
+        assert_eq!(
+            split_fields("1, \"Tanaka, Hiro\" ,\"say \"\"hi\"\"\",", ','),
+            Some(vec![
+                String::from("1"),
+                String::from("Tanaka, Hiro"),
+                String::from("say \"hi\""),
+                String::new()
+            ])
+        );
+        assert_eq!(
+            split_fields("1\tA, B\t1500", '\t'),
+            Some(vec![
+                String::from("1"),
+                String::from("A, B"),
+                String::from("1500")
+            ])
+        );
+        assert_eq!(split_fields("1,\"unterminated", ','), None);
+        assert_eq!(detect_delimiter("id\tname"), '\t');
+        assert_eq!(detect_delimiter("id,name"), ',');
+    }
+}
@@ -0,0 +1,257 @@
+use crate::galois_field::GaloisField;
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::minimap::Minimap;
+use crate::seating_shuffle::make_shuffled_seating;
+use crate::winds::balance_winds;
+
+/// Seatings for several sessions where no pair of players meets twice, if such schedule
+/// can be constructed for players count (see get_design_sessions_count).
+/// Otherwise sessions are seated one by one with shuffled seating.
+/// Returns seating for each session.
+pub fn make_scheduled_seatings(
+    players_map: &PlayersMap,
+    sessions_count: usize,
+    rand_factor: u64,
+) -> Vec<PlayersMap> {
+    let design = make_design(players_map.len())
+        .filter(|rounds| rounds.len() >= sessions_count && sessions_count > 0);
+
+    match design {
+        Some(rounds) => make_seatings_from_rounds(&rounds[..sessions_count], players_map),
+        None => {
+            let mut previous_seatings: Vec<Vec<u32>> = Vec::new();
+            let mut result = Vec::new();
+            for session in 0..sessions_count {
+                let seating = make_shuffled_seating(
+                    players_map,
+                    &previous_seatings,
+                    1,
+                    WindPolicy::BalancedByHistory,
+                    rand_factor + session as u64,
+                );
+                for table in seating.chunks(4) {
+                    previous_seatings.push(table.iter().map(|(id, _)| *id).collect());
+                }
+                result.push(seating);
+            }
+            result
+        }
+    }
+}
+
+/// Replace player indices in rounds with players from the map and balance winds across sessions
+pub(crate) fn make_seatings_from_rounds(
+    rounds: &[Vec<Vec<u32>>],
+    players_map: &PlayersMap,
+) -> Vec<PlayersMap> {
+    let sessions: Vec<Vec<Vec<u32>>> = rounds
+        .iter()
+        .map(|round| {
+            round
+                .iter()
+                .map(|table| {
+                    table
+                        .iter()
+                        .map(|index| players_map[*index as usize].0)
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut player_to_rating = Minimap::new(players_map.len());
+    player_to_rating.fill_with(players_map);
+
+    balance_winds(&sessions)
+        .sessions
+        .iter()
+        .map(|session| {
+            session
+                .iter()
+                .flatten()
+                .map(|id| (*id, player_to_rating.get_value(*id).unwrap()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Max sessions count without repeated pairs which can be constructed for players count,
+/// or 0 if there is no known design:
+/// - 4^n players (16, 64, 256): (4^n - 1) / 3 sessions, affine geometry over GF(4)
+/// - 4q players where q >= 4 is prime power (20, 28, 36, 44, 52...): q sessions, transversal design
+pub fn get_design_sessions_count(players_count: usize) -> usize {
+    make_design(players_count).map_or(0, |rounds| rounds.len())
+}
+
+/// Best of known designs for players count.
+/// Each round is a list of tables; players are denoted by indices 0..players_count.
+pub(crate) fn make_design(players_count: usize) -> Option<Vec<Vec<Vec<u32>>>> {
+    let affine = make_affine_design(players_count);
+    let transversal = make_transversal_design(players_count);
+
+    match (affine, transversal) {
+        (Some(a), Some(t)) => Some(if a.len() >= t.len() { a } else { t }),
+        (a, t) => a.or(t),
+    }
+}
+
+/// Lines of affine space AG(n, 4); lines with the same direction make a round.
+/// Every two points lie on exactly one line, so every pair of players meets exactly once.
+fn make_affine_design(players_count: usize) -> Option<Vec<Vec<Vec<u32>>>> {
+    let mut dimension = 0;
+    let mut size = 1;
+    while size < players_count {
+        size *= 4;
+        dimension += 1;
+    }
+    if size != players_count || dimension < 2 {
+        return None;
+    }
+
+    let field = GaloisField::new(4)?;
+    let to_vector = |mut index: u32| -> Vec<u32> {
+        let mut vector = Vec::new();
+        for _ in 0..dimension {
+            vector.push(index % 4);
+            index /= 4;
+        }
+        vector
+    };
+    let from_vector = |vector: &[u32]| vector.iter().rev().fold(0, |acc, x| acc * 4 + x);
+
+    let mut rounds = Vec::new();
+    for direction_index in 1..players_count as u32 {
+        let direction = to_vector(direction_index);
+        // Take only normalized directions: last non-zero coordinate equals 1
+        if direction.iter().rev().find(|x| **x != 0) != Some(&1) {
+            continue;
+        }
+
+        let mut visited = vec![false; players_count];
+        let mut round = Vec::new();
+        for start in 0..players_count as u32 {
+            if visited[start as usize] {
+                continue;
+            }
+            let point = to_vector(start);
+            let line: Vec<u32> = (0..4)
+                .map(|t| {
+                    let moved: Vec<u32> = point
+                        .iter()
+                        .zip(direction.iter())
+                        .map(|(x, d)| field.add(*x, field.mul(t, *d)))
+                        .collect();
+                    from_vector(&moved)
+                })
+                .collect();
+            line.iter()
+                .for_each(|index| visited[*index as usize] = true);
+            round.push(line);
+        }
+        rounds.push(round);
+    }
+
+    Some(rounds)
+}
+
+/// Resolvable transversal design TD(4, q): player (group i, element x) sits in round a
+/// at table b if x = a * g_i + b, where g_i are distinct elements of GF(q).
+/// Players of different groups meet exactly once, players of the same group never meet.
+fn make_transversal_design(players_count: usize) -> Option<Vec<Vec<Vec<u32>>>> {
+    if !players_count.is_multiple_of(4) || players_count < 16 {
+        return None;
+    }
+    let order = (players_count / 4) as u32;
+    let field = GaloisField::new(order)?;
+
+    let rounds = (0..order)
+        .map(|a| {
+            (0..order)
+                .map(|b| {
+                    (0..4)
+                        .map(|group| group * order + field.add(field.mul(a, group), b))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(rounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check every player sits once per round and no pair meets twice
+    fn assert_no_repeats(rounds: &[Vec<Vec<u32>>], players_count: usize) {
+        let mut met = vec![false; players_count * players_count];
+        for round in rounds {
+            let mut seated: Vec<u32> = round.iter().flatten().copied().collect();
+            seated.sort();
+            assert_eq!(seated, (0..players_count as u32).collect::<Vec<u32>>());
+
+            for table in round {
+                assert_eq!(table.len(), 4);
+                for i in 0..4 {
+                    for j in i + 1..4 {
+                        let index = table[i] as usize * players_count + table[j] as usize;
+                        let reverse = table[j] as usize * players_count + table[i] as usize;
+                        assert!(!met[index]);
+                        met[index] = true;
+                        met[reverse] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_design_sessions_count() {
+        assert_eq!(get_design_sessions_count(16), 5);
+        assert_eq!(get_design_sessions_count(20), 5);
+        assert_eq!(get_design_sessions_count(28), 7);
+        assert_eq!(get_design_sessions_count(36), 9);
+        assert_eq!(get_design_sessions_count(64), 21);
+        assert_eq!(get_design_sessions_count(24), 0);
+        assert_eq!(get_design_sessions_count(12), 0);
+    }
+
+    #[test]
+    fn test_designs_have_no_repeats() {
+        for players_count in [16, 20, 28, 36, 64] {
+            assert_no_repeats(&make_design(players_count).unwrap(), players_count);
+        }
+    }
+
+    #[test]
+    fn test_make_scheduled_seatings() {
+        let players: PlayersMap = (1..=16).map(|id| (id, 1500 + id as i32)).collect();
+        let seatings = make_scheduled_seatings(&players, 5, 12345);
+        assert_eq!(seatings.len(), 5);
+
+        // Map ids back to indices to check the property on real seatings
+        let rounds: Vec<Vec<Vec<u32>>> = seatings
+            .iter()
+            .map(|seating| {
+                seating
+                    .chunks(4)
+                    .map(|table| table.iter().map(|(id, _)| id - 1).collect())
+                    .collect()
+            })
+            .collect();
+        assert_no_repeats(&rounds, 16);
+        assert!(seatings
+            .iter()
+            .flatten()
+            .all(|(id, rating)| *rating == 1500 + *id as i32));
+    }
+
+    #[test]
+    fn test_make_scheduled_seatings_fallback() {
+        let players: PlayersMap = (1..=24).map(|id| (id, 1500)).collect();
+        let seatings = make_scheduled_seatings(&players, 3, 12345);
+        assert_eq!(seatings.len(), 3);
+        assert!(seatings.iter().all(|seating| seating.len() == 24));
+    }
+}
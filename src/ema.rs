@@ -0,0 +1,516 @@
+use crate::delimited::{find_columns, numbered_lines, split_fields};
+use crate::interfaces::{PlayersMap, Standing};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Player of EMA-style results file
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmaPlayer {
+    pub id: u32,
+    pub name: String,
+    pub country: String,
+    pub club: String,
+}
+
+/// Tournament results imported from EMA-style file
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmaResults {
+    pub players: Vec<EmaPlayer>,
+    pub previous_seatings: Vec<Vec<u32>>, // Tables of all sessions, ordered by session and table
+    pub standings: Vec<Standing>,         // Points are total score in tenths of point
+}
+
+/// Players imported from EMA-style ranking file
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmaRanking {
+    pub players: Vec<EmaPlayer>,
+    pub ratings: Vec<i32>, // Rating of each player in tenths of point, in order of players
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmaError {
+    pub line: usize, // 1-based line number, 0 if error is not related to single line
+    pub message: String,
+}
+
+impl fmt::Display for EmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for EmaError {}
+
+const COLUMNS: [&str; 7] = [
+    "player", "name", "country", "club", "session", "table", "score",
+];
+const RANKING_COLUMNS: [&str; 5] = ["player", "name", "country", "club", "rating"];
+
+/// One row of results file
+struct EmaRow {
+    line: usize,
+    player: EmaPlayer,
+    session: u32,
+    table: u32,
+    score: i32, // tenths of point
+}
+
+/// Import EMA-style results: comma separated file with header row containing columns
+/// player (numeric id), name, country, club, session, table and score, in any order.
+/// Each row is a result of one player at one table; players are seated in order of rows.
+/// Fields may be quoted with double quotes, empty lines are skipped.
+/// Scores are stored in tenths of point, e.g. 45.5 becomes 455.
+pub fn import_ema_results(text: &str) -> Result<EmaResults, EmaError> {
+    let rows = parse_rows(text)?;
+
+    // Players and their standings in order of first row, indexed by player id
+    let mut players: Vec<EmaPlayer> = Vec::new();
+    let mut standings: Vec<Standing> = Vec::new();
+    let mut player_index: HashMap<u32, usize> = HashMap::new();
+    for row in &rows {
+        match player_index.get(&row.player.id) {
+            Some(index) if players[*index] != row.player => {
+                return Err(EmaError {
+                    line: row.line,
+                    message: format!(
+                        "player {} has different name, country or club than in previous rows",
+                        row.player.id
+                    ),
+                });
+            }
+            Some(_) => {}
+            None => {
+                player_index.insert(row.player.id, players.len());
+                players.push(row.player.clone());
+                standings.push(Standing {
+                    id: row.player.id,
+                    points: 0,
+                    uma: 0,
+                    placements: [0; 4],
+                });
+            }
+        }
+    }
+
+    // Group rows into tables, ordered by session and table number
+    let mut keys: Vec<(u32, u32)> = rows.iter().map(|row| (row.session, row.table)).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut previous_seatings = Vec::new();
+
+    for (session, table) in keys {
+        let table_rows: Vec<&EmaRow> = rows
+            .iter()
+            .filter(|row| row.session == session && row.table == table)
+            .collect();
+        if table_rows.len() != 4 {
+            return Err(EmaError {
+                line: table_rows[0].line,
+                message: format!(
+                    "session {session}, table {table} has {} players instead of 4",
+                    table_rows.len()
+                ),
+            });
+        }
+
+        for row in &table_rows {
+            // Place index is count of players with higher score, equal scores share the place
+            let place = table_rows
+                .iter()
+                .filter(|other| other.score > row.score)
+                .count();
+            // Each row's player is indexed above
+            let standing = &mut standings[player_index[&row.player.id]];
+            standing.points += row.score;
+            standing.placements[place] += 1;
+        }
+
+        previous_seatings.push(table_rows.iter().map(|row| row.player.id).collect());
+    }
+
+    // Each player should play only once per session
+    for row in &rows {
+        let duplicate = rows.iter().find(|other| {
+            other.line > row.line
+                && other.session == row.session
+                && other.player.id == row.player.id
+        });
+        if let Some(duplicate) = duplicate {
+            return Err(EmaError {
+                line: duplicate.line,
+                message: format!(
+                    "player {} is seated twice in session {}",
+                    row.player.id, row.session
+                ),
+            });
+        }
+    }
+
+    Ok(EmaResults {
+        players,
+        previous_seatings,
+        standings,
+    })
+}
+
+/// Import EMA-style ranking: comma separated file with header row containing columns
+/// player (numeric id), name, country, club and rating, in any order, one row per player.
+/// Ratings are stored in tenths of point like scores of results file.
+pub fn import_ema_ranking(text: &str) -> Result<EmaRanking, EmaError> {
+    let mut ranking = EmaRanking {
+        players: Vec::new(),
+        ratings: Vec::new(),
+    };
+
+    for (line, fields) in parse_columns(text, &RANKING_COLUMNS)? {
+        let player = parse_player(line, &fields)?;
+        if ranking.players.iter().any(|other| other.id == player.id) {
+            return Err(EmaError {
+                line,
+                message: format!("player {} is listed more than once", player.id),
+            });
+        }
+        ranking
+            .ratings
+            .push(parse_score(&fields[4]).ok_or(EmaError {
+                line,
+                message: format!("invalid rating: '{}'", fields[4]),
+            })?);
+        ranking.players.push(player);
+    }
+
+    Ok(ranking)
+}
+
+impl EmaRanking {
+    /// Players with their ratings, to be used in seating algorithms
+    pub fn players_map(&self) -> PlayersMap {
+        self.players
+            .iter()
+            .zip(&self.ratings)
+            .map(|(player, rating)| (player.id, *rating))
+            .collect()
+    }
+
+    /// (player_id, team_id) pairs of players from the same club, see EmaResults::club_teams
+    pub fn club_teams(&self) -> Vec<(u32, u32)> {
+        make_teams(
+            self.players
+                .iter()
+                .map(|player| (player.id, player.club.as_str())),
+        )
+    }
+
+    /// (player_id, team_id) pairs of players from the same country, see EmaResults::country_teams
+    pub fn country_teams(&self) -> Vec<(u32, u32)> {
+        make_teams(
+            self.players
+                .iter()
+                .map(|player| (player.id, player.country.as_str())),
+        )
+    }
+}
+
+impl EmaResults {
+    /// Players with total score as rating, to be used in make_swiss_seating
+    pub fn players_map(&self) -> PlayersMap {
+        self.standings
+            .iter()
+            .map(|standing| (standing.id, standing.points))
+            .collect()
+    }
+
+    /// (player_id, team_id) pairs of players from the same club, to be used in
+    /// make_team_seating to avoid seating clubmates at the same table
+    pub fn club_teams(&self) -> Vec<(u32, u32)> {
+        make_teams(
+            self.players
+                .iter()
+                .map(|player| (player.id, player.club.as_str())),
+        )
+    }
+
+    /// (player_id, team_id) pairs of players from the same country, to be used in
+    /// make_team_seating to avoid seating compatriots at the same table
+    pub fn country_teams(&self) -> Vec<(u32, u32)> {
+        make_teams(
+            self.players
+                .iter()
+                .map(|player| (player.id, player.country.as_str())),
+        )
+    }
+}
+
+/// Number groups of players by (player_id, key) pairs; players with empty key are not included
+pub(crate) fn make_teams<'a, I>(players: I) -> Vec<(u32, u32)>
+where
+    I: Iterator<Item = (u32, &'a str)>,
+{
+    let mut keys: Vec<&str> = Vec::new();
+    players
+        .filter(|(_, key)| !key.is_empty())
+        .map(|(id, key)| {
+            let index = match keys.iter().position(|k| *k == key) {
+                Some(index) => index,
+                None => {
+                    keys.push(key);
+                    keys.len() - 1
+                }
+            };
+            (id, index as u32 + 1)
+        })
+        .collect()
+}
+
+fn parse_rows(text: &str) -> Result<Vec<EmaRow>, EmaError> {
+    parse_columns(text, &COLUMNS)?
+        .into_iter()
+        .map(|(line, fields)| {
+            Ok(EmaRow {
+                line,
+                player: parse_player(line, &fields)?,
+                session: parse_number(line, &fields[4], COLUMNS[4])?,
+                table: parse_number(line, &fields[5], COLUMNS[5])?,
+                score: parse_score(&fields[6]).ok_or(EmaError {
+                    line,
+                    message: format!("invalid score: '{}'", fields[6]),
+                })?,
+            })
+        })
+        .collect()
+}
+
+/// Rows of file with header row containing $columns in any order.
+/// Returns (line number, fields in order of $columns) for each row.
+fn parse_columns(text: &str, columns: &[&str]) -> Result<Vec<(usize, Vec<String>)>, EmaError> {
+    let mut lines = numbered_lines(text);
+
+    let (header_line, header) = lines.next().ok_or(EmaError {
+        line: 0,
+        message: String::from("file is empty"),
+    })?;
+    let header = split_line(header, header_line)?;
+    let positions: Vec<usize> = find_columns(&header, columns)
+        .iter()
+        .zip(columns)
+        .map(|(position, column)| {
+            position.ok_or(EmaError {
+                line: header_line,
+                message: format!("missing column '{column}'"),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    lines
+        .map(|(line, text)| {
+            let fields = split_line(text, line)?;
+            if fields.len() != header.len() {
+                return Err(EmaError {
+                    line,
+                    message: format!("expected {} fields, got {}", header.len(), fields.len()),
+                });
+            }
+            let fields = positions
+                .iter()
+                .map(|position| fields[*position].clone())
+                .collect();
+            Ok((line, fields))
+        })
+        .collect()
+}
+
+/// Player of first 4 columns: player, name, country and club
+fn parse_player(line: usize, fields: &[String]) -> Result<EmaPlayer, EmaError> {
+    Ok(EmaPlayer {
+        id: parse_number(line, &fields[0], COLUMNS[0])?,
+        name: fields[1].clone(),
+        country: fields[2].clone(),
+        club: fields[3].clone(),
+    })
+}
+
+fn parse_number(line: usize, value: &str, column: &str) -> Result<u32, EmaError> {
+    value.parse::<u32>().map_err(|_| EmaError {
+        line,
+        message: format!("invalid {column}: '{value}'"),
+    })
+}
+
+/// Parse decimal score into tenths of point
+fn parse_score(score: &str) -> Option<i32> {
+    let value: f64 = score.parse().ok()?;
+    if !value.is_finite() {
+        return None;
+    }
+    Some((value * 10.0).round() as i32)
+}
+
+fn split_line(line: &str, line_number: usize) -> Result<Vec<String>, EmaError> {
+    split_fields(line, ',').ok_or(EmaError {
+        line: line_number,
+        message: String::from("unterminated quoted field"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::WindPolicy;
+    use crate::seating_swiss::make_swiss_seating;
+    use crate::seating_team::make_team_seating;
+
+    const RESULTS: &str = include_str!("../tests/fixtures/ema_results.csv");
+    const INVALID_RESULTS: &str = include_str!("../tests/fixtures/ema_results_invalid.csv");
+    const RANKING: &str = include_str!("../tests/fixtures/ema_ranking.csv");
+
+    #[test]
+    fn test_import_ema_results() {
+        let results = import_ema_results(RESULTS).unwrap();
+
+        assert_eq!(results.players.len(), 8);
+        assert_eq!(results.players[0].name, "Tanaka, Hiro");
+        assert_eq!(
+            results.previous_seatings,
+            vec![
+                vec![10010001, 10010002, 10010003, 10010004],
+                vec![10010005, 10010006, 10010007, 10010008],
+                vec![10010001, 10010006, 10010003, 10010008],
+                vec![10010002, 10010005, 10010004, 10010007],
+            ]
+        );
+
+        assert_eq!(
+            results.standings[0],
+            Standing {
+                id: 10010001,
+                points: 255,
+                uma: 0,
+                placements: [1, 0, 0, 1]
+            }
+        );
+        // Equal scores share the place
+        assert_eq!(results.standings[1].placements, [1, 0, 1, 0]);
+        assert_eq!(results.standings[4].placements, [1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_import_ema_results_errors() {
+        assert_eq!(
+            import_ema_results(INVALID_RESULTS),
+            Err(EmaError {
+                line: 6,
+                message: String::from("invalid score: 'five'")
+            })
+        );
+
+        let text = INVALID_RESULTS.replace(",five", ",5");
+        assert_eq!(
+            import_ema_results(&text).unwrap_err().message,
+            "session 1, table 2 has 1 players instead of 4"
+        );
+
+        assert_eq!(
+            import_ema_results("player,name,country,session,table,score\n")
+                .unwrap_err()
+                .to_string(),
+            "line 1: missing column 'club'"
+        );
+        assert_eq!(
+            import_ema_results(&RESULTS.replace("1,2,-5.0", "1,1,-5.0"))
+                .unwrap_err()
+                .line,
+            2
+        );
+
+        // Player id reappears with another club
+        assert_eq!(
+            import_ema_results(&RESULTS.replace(
+                "10010006,Lars Berg,DEU,Berlin Mahjong,2,1",
+                "10010006,Lars Berg,DEU,Hamburg Riichi,2,1"
+            )),
+            Err(EmaError {
+                line: 12,
+                message: String::from(
+                    "player 10010006 has different name, country or club than in previous rows"
+                )
+            })
+        );
+    }
+
+    #[test]
+    fn test_import_ema_ranking() {
+        let ranking = import_ema_ranking(RANKING).unwrap();
+
+        assert_eq!(ranking.players.len(), 8);
+        assert_eq!(ranking.players[0].name, "Tanaka, Hiro");
+        assert_eq!(ranking.players_map()[0], (10010001, 8125));
+        assert_eq!(ranking.players_map()[7], (10010008, 5805));
+        // Players of Berlin club, player without club is not in any team
+        assert_eq!(
+            ranking
+                .club_teams()
+                .iter()
+                .filter(|(_, team)| *team == 2)
+                .count(),
+            2
+        );
+        assert_eq!(ranking.club_teams().len(), 7);
+        assert_eq!(ranking.country_teams().len(), 8);
+
+        assert_eq!(
+            import_ema_ranking(&RANKING.replace("745", "n/a")),
+            Err(EmaError {
+                line: 4,
+                message: String::from("invalid rating: 'n/a'")
+            })
+        );
+        assert_eq!(
+            import_ema_ranking(&RANKING.replace("10010008", "10010001")),
+            Err(EmaError {
+                line: 9,
+                message: String::from("player 10010001 is listed more than once")
+            })
+        );
+        assert_eq!(
+            import_ema_ranking("player,name,country,club\n")
+                .unwrap_err()
+                .to_string(),
+            "line 1: missing column 'rating'"
+        );
+    }
+
+    #[test]
+    fn test_ema_results_for_seating() {
+        let results = import_ema_results(RESULTS).unwrap();
+
+        let seating = make_swiss_seating(
+            &results.players_map(),
+            &results.previous_seatings,
+            WindPolicy::Fixed,
+            12345,
+        );
+        assert_eq!(seating.len(), 8);
+
+        // Two players of Berlin club are never seated together
+        let teams = results.club_teams();
+        assert_eq!(teams.iter().filter(|(_, team)| *team == 2).count(), 2);
+        let seating = make_team_seating(
+            &results.players_map(),
+            &teams,
+            &results.previous_seatings,
+            WindPolicy::Fixed,
+            12345,
+        )
+        .unwrap();
+        assert!(seating.chunks(4).all(|table| {
+            !(table.iter().any(|(id, _)| *id == 10010002)
+                && table.iter().any(|(id, _)| *id == 10010006))
+        }));
+        assert_eq!(results.country_teams().len(), 8);
+    }
+}
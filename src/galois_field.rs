@@ -0,0 +1,168 @@
+/// Finite field of prime power order.
+/// Elements are numbers 0..order, digits of element in base p are coefficients of polynomial
+/// over GF(p), multiplication is done modulo irreducible polynomial.
+pub struct GaloisField {
+    pub order: u32,
+    add_table: Vec<u32>,
+    mul_table: Vec<u32>,
+}
+
+impl GaloisField {
+    /// Create field of given order, or None if order is not a prime power
+    pub fn new(order: u32) -> Option<GaloisField> {
+        let (p, k) = prime_power(order)?;
+        let modulus = find_irreducible_polynomial(p, k);
+
+        let mut add_table = vec![0; (order * order) as usize];
+        let mut mul_table = vec![0; (order * order) as usize];
+        for a in 0..order {
+            for b in 0..order {
+                let (poly_a, poly_b) = (to_polynomial(a, p, k), to_polynomial(b, p, k));
+                let sum: Vec<u32> = poly_a
+                    .iter()
+                    .zip(poly_b.iter())
+                    .map(|(x, y)| (x + y) % p)
+                    .collect();
+                let product = polynomial_mod(&polynomial_mul(&poly_a, &poly_b, p), &modulus, p);
+                add_table[(a * order + b) as usize] = from_polynomial(&sum, p);
+                mul_table[(a * order + b) as usize] = from_polynomial(&product, p);
+            }
+        }
+
+        Some(GaloisField {
+            order,
+            add_table,
+            mul_table,
+        })
+    }
+
+    pub fn add(&self, a: u32, b: u32) -> u32 {
+        self.add_table[(a * self.order + b) as usize]
+    }
+
+    pub fn mul(&self, a: u32, b: u32) -> u32 {
+        self.mul_table[(a * self.order + b) as usize]
+    }
+}
+
+/// Split number into (p, k) where number = p^k and p is prime
+fn prime_power(number: u32) -> Option<(u32, u32)> {
+    if number < 2 {
+        return None;
+    }
+    let p = (2..=number).find(|divisor| number.is_multiple_of(*divisor))?;
+    let mut rest = number;
+    let mut k = 0;
+    while rest.is_multiple_of(p) {
+        rest /= p;
+        k += 1;
+    }
+    if rest == 1 {
+        Some((p, k))
+    } else {
+        None
+    }
+}
+
+/// Coefficients of polynomial, lowest degree first
+fn to_polynomial(mut value: u32, p: u32, k: u32) -> Vec<u32> {
+    let mut result = Vec::new();
+    for _ in 0..k {
+        result.push(value % p);
+        value /= p;
+    }
+    result
+}
+
+fn from_polynomial(poly: &[u32], p: u32) -> u32 {
+    poly.iter()
+        .rev()
+        .fold(0, |acc, coefficient| acc * p + coefficient)
+}
+
+fn polynomial_mul(a: &[u32], b: &[u32], p: u32) -> Vec<u32> {
+    let mut result = vec![0; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] = (result[i + j] + x * y) % p;
+        }
+    }
+    result
+}
+
+/// Remainder of division by monic polynomial; result has degree lower than divisor
+fn polynomial_mod(dividend: &[u32], divisor: &[u32], p: u32) -> Vec<u32> {
+    let degree = divisor.len() - 1;
+    let mut rest = dividend.to_vec();
+    for i in (degree..rest.len()).rev() {
+        let coefficient = rest[i];
+        if coefficient == 0 {
+            continue;
+        }
+        for (j, divisor_coefficient) in divisor.iter().enumerate() {
+            let index = i - degree + j;
+            rest[index] = (rest[index] + p * p - coefficient * divisor_coefficient % p) % p;
+        }
+    }
+    rest.resize(degree, 0);
+    rest
+}
+
+/// Find monic irreducible polynomial of degree k over GF(p) by trial division
+fn find_irreducible_polynomial(p: u32, k: u32) -> Vec<u32> {
+    let monic = |lower: u32, degree: u32| {
+        let mut poly = to_polynomial(lower, p, degree);
+        poly.push(1);
+        poly
+    };
+
+    (0..p.pow(k))
+        .map(|lower| monic(lower, k))
+        .find(|candidate| {
+            (1..=k / 2).all(|degree| {
+                (0..p.pow(degree)).all(|lower| {
+                    polynomial_mod(candidate, &monic(lower, degree), p)
+                        .iter()
+                        .any(|coefficient| *coefficient != 0)
+                })
+            })
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_power() {
+        assert_eq!(prime_power(7), Some((7, 1)));
+        assert_eq!(prime_power(9), Some((3, 2)));
+        assert_eq!(prime_power(16), Some((2, 4)));
+        assert_eq!(prime_power(12), None);
+        assert_eq!(prime_power(1), None);
+    }
+
+    #[test]
+    fn test_field_axioms() {
+        for order in [4, 5, 8, 9, 16] {
+            let field = GaloisField::new(order).unwrap();
+            for a in 1..order {
+                // Every non-zero element has multiplicative inverse
+                assert!((1..order).any(|b| field.mul(a, b) == 1));
+                // And additive inverse
+                assert!((0..order).any(|b| field.add(a, b) == 0));
+            }
+            for a in 0..order {
+                for b in 0..order {
+                    for c in 0..order {
+                        assert_eq!(
+                            field.mul(a, field.add(b, c)),
+                            field.add(field.mul(a, b), field.mul(a, c))
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
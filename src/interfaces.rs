@@ -13,3 +13,160 @@ pub struct TableWithRating {
     pub players: Vec<(u32, i32)>, // Array of (id, rating) pairs
     pub max_rating: i32,          // Max rating at table
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct BalancedSeating {
+    pub seating: Vec<(u32, i32)>, // Array of (id, rating) pairs, 4 players per table
+    pub rating_variance: f64,     // Variance of average rating of tables
+    pub repeats: u32,             // Count of pairs which already played together
+}
+
+/// Where players who can't be seated with desired interval are placed in interval seating.
+/// Example for 5 tables (20 players sorted by rating) and step 2:
+/// - Top: tables 1-2-3-4, then interval part 5-7-9-11, 13-15-17-19, 6-8-10-12, 14-16-18-20
+/// - Middle: table 9-10-11-12, interval part 1-3-5-7, 13-15-17-19, 2-4-6-8, 14-16-18-20
+/// - Bottom: interval part 1-3-5-7, 9-11-13-15, 2-4-6-8, 10-12-14-16, then table 17-18-19-20
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum RemainderPlacement {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IntervalSegment {
+    pub tables: usize, // Tables count in segment, 0 means all remaining tables
+    pub step: usize,   // Interval between players at the table
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IntervalConfig {
+    pub segments: Vec<IntervalSegment>, // Segments of rating list, from top to bottom
+    pub remainder: RemainderPlacement,  // Placement of players which can't be seated with interval
+    pub max_displacement: usize,        // Max places a player can be moved to avoid repeated pairs
+}
+
+/// How players advancing to finals are grouped, by their standings.
+/// Example for 8 players:
+/// - Straight: 1-2-3-4, 5-6-7-8
+/// - Snake: 1-4-5-8, 2-3-6-7
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum FinalsGrouping {
+    Straight,
+    Snake,
+}
+
+/// How winds are assigned at finals tables
+/// - ByStanding: highest ranked player at the table is East, lowest is North
+/// - ReverseStanding: lowest ranked player at the table is East, highest is North
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum FinalsWinds {
+    ByStanding,
+    ReverseStanding,
+}
+
+/// Rule of assigning winds at the table
+/// - Random: random winds using seeded RNG
+/// - BalancedByHistory: players sit to winds they sat less times in previous seatings
+/// - ByRating: player with highest rating sits East
+/// - Fixed: players sit in order produced by seating algorithm
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum WindPolicy {
+    Random,
+    BalancedByHistory,
+    ByRating,
+    Fixed,
+}
+
+/// Result of balance_winds. Balancing is heuristic: $still_unbalanced_players are players it
+/// failed to balance, some of them may still be balanceable by another assignment of winds.
+#[derive(Serialize, Deserialize)]
+pub struct WindsBalance {
+    pub sessions: Vec<Vec<Vec<u32>>>, // Sessions of tables, players at each table in E-S-W-N order
+    pub still_unbalanced_players: Vec<u32>, // Players whose counts of winds differ by more than one after balancing
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SeatingWithDealers {
+    pub seating: Vec<(u32, i32)>, // Array of (id, rating) pairs, 4 players per table
+    pub first_dealers: Vec<u32>,  // Id of player who deals first at each table
+}
+
+/// Result of social golfer solver
+#[derive(Serialize, Deserialize)]
+pub struct GolferSchedule {
+    pub sessions: Vec<Vec<(u32, i32)>>, // Seating of each session, no pair of players meets twice
+    pub proven_max: bool, // True if it's proven that no more sessions can be added without repeats
+}
+
+/// Result of swiss seating with repeats of players meetings
+#[derive(Serialize, Deserialize)]
+pub struct SwissSeating {
+    pub seating: Vec<(u32, i32)>, // Array of (id, rating) pairs, 4 players per table
+    pub repeats: u32, // Total count of previous meetings of players seated at the same tables
+    pub max_repeats: u32, // Max count of previous meetings of two players seated at the same table
+}
+
+/// Result of exact swiss seating
+#[derive(Serialize, Deserialize)]
+pub struct ExactSwissSeating {
+    pub seating: Vec<(u32, i32)>, // Array of (id, rating) pairs, 4 players per table
+    pub repeats: u32,             // Total count of repeated meetings in seating
+    pub lower_bound: u32,         // Proven lower bound of repeated meetings count
+    pub optimal: bool,            // True if seating is proven to be optimal
+}
+
+/// Player's tournament standing used to rank players in swiss seating
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Standing {
+    pub id: u32,
+    pub points: i32,          // Tournament points, main ranking criterion
+    pub uma: i32,             // Total uma
+    pub placements: [u32; 4], // Count of 1st, 2nd, 3rd and 4th places
+}
+
+/// Tie-break rules applied in given order to players with equal points.
+/// Players still tied after all rules are ordered by id.
+/// - Uma: higher total uma is better
+/// - Placements: more 1st places is better, then more 2nd places, then more 3rd places
+/// - FewerLastPlaces: less 4th places is better
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum TieBreak {
+    Uma,
+    Placements,
+    FewerLastPlaces,
+}
+
+/// Output format of printable seating sheets
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum RenderFormat {
+    Html,
+    Markdown,
+}
+
+/// Quality of seating compared with previous seatings, see make_quality_report
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct QualityReport {
+    pub repeats: u32, // Total count of previous meetings of players seated at the same tables
+    pub max_repeats: u32, // Max count of previous meetings of two players seated at the same table
+    pub repeated_pairs: Vec<(u32, u32, u32)>, // (player1, player2, previous meetings) of players who met before
+    pub rating_variance: f64,                 // Variance of average rating of tables
+    pub max_rating_spread: i32, // Max difference between ratings of players at the same table
+}
+
+/// Player who must be seated at given table
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct PinnedSeat {
+    pub id: u32,
+    pub table: usize,        // Index of table in seating, 0-based
+    pub seat: Option<usize>, // Index of seat at the table (0 - East, ..., 3 - North), any seat if not set
+}
+
+/// Constraints and claims of seating algorithm to be checked by verify_seating
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct SeatingConstraints {
+    pub forbidden: Vec<(u32, u32)>, // Pairs of players which must not be seated at the same table
+    pub pinned: Vec<PinnedSeat>,    // Players which must be seated at given tables
+    pub claimed_repeats: Option<u32>, // Total count of repeated meetings reported by algorithm
+    pub claimed_max_repeats: Option<u32>, // Max count of previous meetings of players seated together
+}
@@ -3,7 +3,7 @@ use crate::interfaces::PlayersMap;
 /// Format seating data for better view
 pub fn make_intersections_table(
     seating: &PlayersMap,
-    previous_seatings: &Vec<Vec<u32>>,
+    previous_seatings: &[Vec<u32>],
 ) -> Vec<(u32, u32, u32)> {
     let possible_intersections = vec![[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]];
 
@@ -13,7 +13,7 @@ pub fn make_intersections_table(
         new_seating_chunks.push(table);
     }
 
-    let mut all_seatings = previous_seatings.clone();
+    let mut all_seatings = previous_seatings.to_vec();
     all_seatings.extend(new_seating_chunks);
 
     let mut intersection_data: Vec<(u32, u32, u32)> = Vec::new();
@@ -21,7 +21,7 @@ pub fn make_intersections_table(
         for intersection in &possible_intersections {
             let item = intersection_data
                 .iter()
-                .position(|x| (*x).0 == game[intersection[0]] && (*x).1 == game[intersection[1]]);
+                .position(|x| x.0 == game[intersection[0]] && x.1 == game[intersection[1]]);
             match item {
                 Some(i) => intersection_data[i].2 += 1,
                 None => {
@@ -31,7 +31,7 @@ pub fn make_intersections_table(
         }
     }
 
-    intersection_data.iter().map(|x| (*x).clone()).collect()
+    intersection_data.to_vec()
 }
 
 #[cfg(test)]
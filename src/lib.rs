@@ -1,16 +1,114 @@
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "capi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "capi", deny(unsafe_code))]
 #![allow(dead_code)]
+#[cfg(feature = "capi")]
+pub mod capi;
+mod delimited;
+mod designs;
+mod ema;
+mod galois_field;
 mod interfaces;
 mod intersections;
 mod matrix;
 mod minimap;
+mod pantheon;
 mod primes;
+#[cfg(feature = "python")]
+mod python;
+mod quality;
+mod render;
+mod roster;
+mod seating_balanced;
+mod seating_finals;
 mod seating_interval;
+mod seating_pairs;
 mod seating_shuffle;
+mod seating_snake;
 mod seating_swiss;
+mod seating_swiss_exact;
+mod seating_team;
+#[cfg(feature = "server")]
+pub mod server;
 mod shuffle;
+mod social_golfer;
+mod standings;
+pub mod strict;
+mod validation;
+mod verification;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod winds;
 
+pub use crate::designs::get_design_sessions_count;
+pub use crate::designs::make_scheduled_seatings;
+pub use crate::ema::import_ema_ranking;
+pub use crate::ema::import_ema_results;
+pub use crate::ema::EmaError;
+pub use crate::ema::EmaPlayer;
+pub use crate::ema::EmaRanking;
+pub use crate::ema::EmaResults;
+pub use crate::interfaces::BalancedSeating;
+pub use crate::interfaces::ExactSwissSeating;
+pub use crate::interfaces::FinalsGrouping;
+pub use crate::interfaces::FinalsWinds;
+pub use crate::interfaces::GolferSchedule;
+pub use crate::interfaces::IntervalConfig;
+pub use crate::interfaces::IntervalSegment;
+pub use crate::interfaces::PinnedSeat;
 pub use crate::interfaces::PlayersMap;
+pub use crate::interfaces::QualityReport;
+pub use crate::interfaces::RemainderPlacement;
+pub use crate::interfaces::RenderFormat;
+pub use crate::interfaces::SeatingConstraints;
+pub use crate::interfaces::SeatingWithDealers;
+pub use crate::interfaces::Standing;
+pub use crate::interfaces::SwissSeating;
+pub use crate::interfaces::TieBreak;
+pub use crate::interfaces::WindPolicy;
+pub use crate::interfaces::WindsBalance;
+pub use crate::intersections::make_intersections_table;
+#[cfg(feature = "json")]
+pub use crate::pantheon::read_pantheon_event;
+#[cfg(feature = "json")]
+pub use crate::pantheon::write_pantheon_seating;
+pub use crate::pantheon::PantheonError;
+pub use crate::pantheon::PantheonEvent;
+pub use crate::pantheon::PantheonGame;
+pub use crate::pantheon::PantheonGames;
+pub use crate::pantheon::PantheonPlayer;
+pub use crate::quality::make_quality_report;
+pub use crate::render::render_itineraries;
+pub use crate::render::render_players_list;
+pub use crate::render::render_table_sheets;
+pub use crate::roster::import_roster;
+pub use crate::roster::Roster;
+pub use crate::roster::RosterError;
+pub use crate::roster::RosterFile;
+pub use crate::roster::RosterPlayer;
+pub use crate::seating_balanced::make_balanced_seating;
+pub use crate::seating_finals::make_finals_seating;
+pub use crate::seating_interval::make_configured_interval_seating;
 pub use crate::seating_interval::make_interval_seating;
+pub use crate::seating_interval::make_interval_seating_with_history;
+pub use crate::seating_pairs::make_pairs_seating;
+pub use crate::seating_pairs::PairsError;
 pub use crate::seating_shuffle::make_shuffled_seating;
+pub use crate::seating_snake::make_snake_seating;
+pub use crate::seating_snake::SnakeError;
+pub use crate::seating_swiss::make_cancellable_swiss_seating;
+pub use crate::seating_swiss::make_mcmahon_seating;
 pub use crate::seating_swiss::make_swiss_seating;
+pub use crate::seating_swiss::make_swiss_seating_by_standings;
+pub use crate::seating_swiss::make_swiss_seating_with_repeats;
+pub use crate::seating_swiss_exact::make_exact_swiss_seating;
+pub use crate::seating_team::make_team_seating;
+pub use crate::seating_team::TeamError;
+pub use crate::social_golfer::make_social_golfer_schedule;
+pub use crate::standings::compare_standings;
+pub use crate::validation::validate;
+pub use crate::validation::Diagnostic;
+pub use crate::verification::verify_seating;
+pub use crate::verification::SeatingReport;
+pub use crate::verification::Violation;
+pub use crate::winds::balance_winds;
+pub use crate::winds::choose_first_dealers;
@@ -1,6 +1,7 @@
 use crate::primes::get_closest_prime;
 use std::cmp::{max, min};
 
+#[derive(Clone)]
 pub struct Matrix<T> {
     factor: usize,
     orig_size: usize,
@@ -49,14 +49,14 @@ impl<T: Clone> Minimap<T> {
     pub fn all(&self, cb: fn(v: &T) -> bool) -> bool {
         self.elements.iter().all(|x| {
             if x.1.is_none() {
-                return true;
+                true
             } else {
                 cb(&x.1.clone().unwrap())
             }
         })
     }
 
-    pub fn fill_with(&mut self, value: &Vec<(u32, T)>) {
+    pub fn fill_with(&mut self, value: &[(u32, T)]) {
         value.iter().for_each(|(id, value)| {
             self.set_value(*id, value.clone());
         })
@@ -64,6 +64,7 @@ impl<T: Clone> Minimap<T> {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use crate::minimap::Minimap;
 
@@ -0,0 +1,365 @@
+//! Event data exchanged with Pantheon (Mimir API). Event is read from two API responses:
+//!
+//! ```text
+//! getRatingTable: [{"id": u32, "title": string, "rating": number, ...}, ...]
+//! getLastGames:   {"games": [{"hash": string, "date": "YYYY-MM-DD hh:mm:ss",
+//!                             "players": [u32, x4] (E, S, W, N), ...}, ...],
+//!                  "players": {...}}
+//! ```
+//! Responses may be wrapped in JSON-RPC envelope ({"jsonrpc": "2.0", "id": ..., "result": ...}),
+//! unknown fields are ignored. New seating is written as manual seating description accepted by
+//! makeManualSeating: one table per line, player ids separated by spaces from East to North.
+//! See tests/fixtures/pantheon_rating_table.json and tests/fixtures/pantheon_games.json.
+
+use crate::interfaces::PlayersMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Pantheon event: registered players and played games
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PantheonEvent {
+    pub players: Vec<PantheonPlayer>,
+    pub games: Vec<PantheonGame>,
+}
+
+/// Row of rating table
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PantheonPlayer {
+    pub id: u32,
+    #[serde(default)]
+    pub title: String,
+    pub rating: f64, // Rounded to integer rating for seating algorithms
+}
+
+/// Game of getLastGames response
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PantheonGame {
+    #[serde(default)]
+    pub hash: String,
+    #[serde(default)]
+    pub date: String, // Games are ordered by date, empty for games added by add_session
+    pub players: Vec<u32>, // Player ids from East to North
+}
+
+/// getLastGames response, players info is not used
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PantheonGames {
+    pub games: Vec<PantheonGame>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum PantheonError {
+    Json(String),
+    DuplicatePlayer { player_id: u32 }, // Player is listed in rating table more than once
+    InvalidGame { game: usize, hash: String }, // Game doesn't have exactly 4 players
+    UnknownPlayer { game: usize, player_id: u32 }, // Player of game is not in rating table
+    RepeatedPlayer { game: usize, player_id: u32 }, // Player takes several seats in game
+}
+
+impl fmt::Display for PantheonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PantheonError::Json(message) => write!(f, "invalid event JSON: {message}"),
+            PantheonError::DuplicatePlayer { player_id } => {
+                write!(f, "player {player_id} is listed more than once")
+            }
+            PantheonError::InvalidGame { game, hash } => {
+                write!(f, "game #{game} ({hash}) should have 4 players")
+            }
+            PantheonError::UnknownPlayer { game, player_id } => {
+                write!(f, "game #{game}: unknown player {player_id}")
+            }
+            PantheonError::RepeatedPlayer { game, player_id } => {
+                write!(f, "game #{game}: player {player_id} takes several seats")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PantheonError {}
+
+impl PantheonEvent {
+    /// Make event from players list and previous seatings, each table is a game
+    pub fn from_seatings(
+        players_map: &PlayersMap,
+        previous_seatings: &[Vec<u32>],
+    ) -> PantheonEvent {
+        PantheonEvent {
+            players: players_map
+                .iter()
+                .map(|(id, rating)| PantheonPlayer {
+                    id: *id,
+                    title: String::new(),
+                    rating: *rating as f64,
+                })
+                .collect(),
+            games: previous_seatings
+                .iter()
+                .map(|table| PantheonGame {
+                    hash: String::new(),
+                    date: String::new(),
+                    players: table.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Players list to be passed to seating algorithms
+    pub fn players_map(&self) -> PlayersMap {
+        self.players
+            .iter()
+            .map(|player| (player.id, player.rating.round() as i32))
+            .collect()
+    }
+
+    /// Check that players are listed once and each game has 4 different registered players
+    pub fn check(&self) -> Result<(), PantheonError> {
+        for (index, player) in self.players.iter().enumerate() {
+            if self.players[..index].iter().any(|p| p.id == player.id) {
+                return Err(PantheonError::DuplicatePlayer {
+                    player_id: player.id,
+                });
+            }
+        }
+
+        for (index, game) in self.games.iter().enumerate() {
+            if game.players.len() != 4 {
+                return Err(PantheonError::InvalidGame {
+                    game: index + 1,
+                    hash: game.hash.clone(),
+                });
+            }
+            for (seat, player_id) in game.players.iter().enumerate() {
+                if !self.players.iter().any(|p| p.id == *player_id) {
+                    return Err(PantheonError::UnknownPlayer {
+                        game: index + 1,
+                        player_id: *player_id,
+                    });
+                }
+                if game.players[..seat].contains(player_id) {
+                    return Err(PantheonError::RepeatedPlayer {
+                        game: index + 1,
+                        player_id: *player_id,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Previous seatings of all games ordered by date, players at each table are ordered by seat
+    pub fn previous_seatings(&self) -> Result<Vec<Vec<u32>>, PantheonError> {
+        self.check()?;
+        let mut games: Vec<&PantheonGame> = self.games.iter().collect();
+        games.sort_by(|a, b| a.date.cmp(&b.date));
+        Ok(games.iter().map(|game| game.players.clone()).collect())
+    }
+
+    /// Append games with seating produced by seating algorithm, 4 players per table
+    pub fn add_session(&mut self, seating: &PlayersMap) {
+        self.games
+            .extend(seating.chunks(4).map(|table| PantheonGame {
+                hash: String::new(),
+                date: String::new(),
+                players: table.iter().map(|item| item.0).collect(),
+            }));
+    }
+}
+
+/// Response body, with or without JSON-RPC envelope
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PantheonResponse<T> {
+    JsonRpc { result: T },
+    Result(T),
+}
+
+#[cfg(feature = "json")]
+fn parse_response<T>(json: &str) -> Result<T, PantheonError>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    match serde_json::from_str(json).map_err(|e| PantheonError::Json(e.to_string()))? {
+        PantheonResponse::JsonRpc { result } => Ok(result),
+        PantheonResponse::Result(result) => Ok(result),
+    }
+}
+
+/// Parse getRatingTable and getLastGames responses of Pantheon event
+#[cfg(feature = "json")]
+pub fn read_pantheon_event(
+    rating_table: &str,
+    games: &str,
+) -> Result<PantheonEvent, PantheonError> {
+    let event = PantheonEvent {
+        players: parse_response(rating_table)?,
+        games: parse_response::<PantheonGames>(games)?.games,
+    };
+    // Check games right away, so errors are reported on import
+    event.check()?;
+    Ok(event)
+}
+
+/// Write seating as manual seating description: one table per line, from East to North
+pub fn write_pantheon_seating(seating: &PlayersMap) -> String {
+    seating
+        .chunks(4)
+        .map(|table| {
+            let ids: Vec<String> = table.iter().map(|item| item.0.to_string()).collect();
+            ids.join(" ") + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_event() -> PantheonEvent {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1600 - id as i32 * 10)).collect();
+        let previous_seatings = vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![1, 5, 2, 6],
+            vec![3, 7, 4, 8],
+        ];
+        PantheonEvent::from_seatings(&players, &previous_seatings)
+    }
+
+    #[test]
+    fn test_event_seatings() {
+        let mut event = make_event();
+        assert_eq!(event.games.len(), 4);
+        assert_eq!(event.players_map()[0], (1, 1590));
+        assert_eq!(
+            event.previous_seatings().unwrap(),
+            vec![
+                vec![1, 2, 3, 4],
+                vec![5, 6, 7, 8],
+                vec![1, 5, 2, 6],
+                vec![3, 7, 4, 8]
+            ]
+        );
+
+        event.add_session(&vec![(8, 0), (1, 0), (6, 0), (3, 0)]);
+        assert_eq!(event.previous_seatings().unwrap()[4], vec![8, 1, 6, 3]);
+    }
+
+    #[test]
+    fn test_event_seatings_are_ordered_by_date() {
+        let mut event = make_event();
+        event.games[0].date = String::from("2024-05-02 12:00:00");
+        event.games[1].date = String::from("2024-05-01 18:30:00");
+        event.players[0].rating = 1589.5;
+
+        assert_eq!(event.players_map()[0], (1, 1590));
+        assert_eq!(
+            event.previous_seatings().unwrap()[2..],
+            [vec![5, 6, 7, 8], vec![1, 2, 3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_event_errors() {
+        let mut event = make_event();
+        event.games[1].players.pop();
+        assert_eq!(
+            event.previous_seatings(),
+            Err(PantheonError::InvalidGame {
+                game: 2,
+                hash: String::new()
+            })
+        );
+
+        let mut event = make_event();
+        event.games[0].players[2] = 42;
+        assert_eq!(
+            event.previous_seatings(),
+            Err(PantheonError::UnknownPlayer {
+                game: 1,
+                player_id: 42
+            })
+        );
+
+        let mut event = make_event();
+        event.games[2].players[3] = 1;
+        assert_eq!(
+            event.previous_seatings(),
+            Err(PantheonError::RepeatedPlayer {
+                game: 3,
+                player_id: 1
+            })
+        );
+
+        let mut event = make_event();
+        event.players[4].id = 2;
+        assert_eq!(
+            event.check(),
+            Err(PantheonError::DuplicatePlayer { player_id: 2 })
+        );
+    }
+
+    #[test]
+    fn test_write_pantheon_seating() {
+        let seating: PlayersMap = (1..=8).map(|id| (id, 0)).collect();
+        assert_eq!(write_pantheon_seating(&seating), "1 2 3 4\n5 6 7 8\n");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_read_pantheon_event() {
+        let rating_table = r#"[
+            {"id": 1, "title": "A", "rating": 1500.5},
+            {"id": 2, "title": "B", "rating": 1400},
+            {"id": 3, "title": "C", "rating": 1300},
+            {"id": 4, "rating": 1200}
+        ]"#;
+        let games = r#"{"jsonrpc": "2.0", "id": 1, "result": {
+            "games": [{"hash": "abc", "date": "2024-05-01 10:00:00", "players": [3, 1, 4, 2]}],
+            "players": {}
+        }}"#;
+        let event = read_pantheon_event(rating_table, games).unwrap();
+        assert_eq!(
+            event.players_map(),
+            vec![(1, 1501), (2, 1400), (3, 1300), (4, 1200)]
+        );
+        assert_eq!(event.previous_seatings().unwrap(), vec![vec![3, 1, 4, 2]]);
+
+        assert!(matches!(
+            read_pantheon_event("{\"players\": 1}", games),
+            Err(PantheonError::Json(_))
+        ));
+        assert_eq!(
+            read_pantheon_event(rating_table, &games.replace("[3, 1, 4, 2]", "[3, 1, 3, 2]")),
+            Err(PantheonError::RepeatedPlayer {
+                game: 1,
+                player_id: 3
+            })
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_read_pantheon_event_fixture() {
+        let event = read_pantheon_event(
+            include_str!("../tests/fixtures/pantheon_rating_table.json"),
+            include_str!("../tests/fixtures/pantheon_games.json"),
+        )
+        .unwrap();
+
+        assert_eq!(event.players_map().len(), 8);
+        assert_eq!(event.players_map()[0], (101, 1720));
+        // Games are listed from the latest one
+        assert_eq!(
+            event.previous_seatings().unwrap(),
+            vec![
+                vec![101, 102, 103, 104],
+                vec![105, 106, 107, 108],
+                vec![106, 101, 108, 103],
+                vec![102, 107, 104, 105],
+            ]
+        );
+    }
+}
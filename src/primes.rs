@@ -20,5 +20,5 @@ pub const PRIMES: [u32; 319] = [
 ];
 
 pub fn get_closest_prime(n: u32) -> usize {
-    PRIMES.iter().find(|&&x| x > n).unwrap().clone() as usize
+    *PRIMES.iter().find(|&&x| x > n).unwrap() as usize
 }
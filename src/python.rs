@@ -0,0 +1,180 @@
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::intersections::make_intersections_table;
+use crate::quality::make_quality_report;
+use crate::seating_interval::make_interval_seating;
+use crate::seating_shuffle::make_shuffled_seating;
+use crate::seating_swiss::make_swiss_seating;
+use crate::seating_team::make_team_seating;
+use crate::validation::{validate, Diagnostic};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+
+/// Parse wind policy name: "random", "balanced_by_history", "by_rating" or "fixed"
+fn parse_wind_policy(name: &str) -> PyResult<WindPolicy> {
+    match name {
+        "random" => Ok(WindPolicy::Random),
+        "balanced_by_history" => Ok(WindPolicy::BalancedByHistory),
+        "by_rating" => Ok(WindPolicy::ByRating),
+        "fixed" => Ok(WindPolicy::Fixed),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown wind policy '{name}', expected one of: random, balanced_by_history, by_rating, fixed"
+        ))),
+    }
+}
+
+/// Check players and previous seatings, problems are reported in one ValueError.
+/// Unknown and repeated players in previous seatings are reported only if $strict is set.
+fn validate_input(
+    players: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    strict: bool,
+) -> PyResult<()> {
+    let diagnostics: Vec<Diagnostic> = validate(players, previous_seatings)
+        .into_iter()
+        .filter(|diagnostic| strict || diagnostic.is_fatal())
+        .collect();
+    if diagnostics.is_empty() {
+        return Ok(());
+    }
+    let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+    Err(PyValueError::new_err(messages.join("; ")))
+}
+
+/// Swiss seating, returns list of (id, rating) tuples, 4 players per table
+#[pyfunction]
+#[pyo3(name = "make_swiss_seating", signature = (players, previous_seatings, wind_policy = "random", rand_factor = 0, strict = false))]
+fn py_make_swiss_seating(
+    players: PlayersMap,
+    previous_seatings: Vec<Vec<u32>>,
+    wind_policy: &str,
+    rand_factor: u64,
+    strict: bool,
+) -> PyResult<PlayersMap> {
+    validate_input(&players, &previous_seatings, strict)?;
+    Ok(make_swiss_seating(
+        &players,
+        &previous_seatings,
+        parse_wind_policy(wind_policy)?,
+        rand_factor,
+    ))
+}
+
+/// Shuffled seating, returns list of (id, rating) tuples, 4 players per table
+#[pyfunction]
+#[pyo3(name = "make_shuffled_seating", signature = (players, previous_seatings, groups_count = 1, wind_policy = "balanced_by_history", rand_factor = 0, strict = false))]
+fn py_make_shuffled_seating(
+    players: PlayersMap,
+    previous_seatings: Vec<Vec<u32>>,
+    groups_count: u32,
+    wind_policy: &str,
+    rand_factor: u64,
+    strict: bool,
+) -> PyResult<PlayersMap> {
+    validate_input(&players, &previous_seatings, strict)?;
+    if groups_count == 0 {
+        return Err(PyValueError::new_err("groups_count should be positive"));
+    }
+    Ok(make_shuffled_seating(
+        &players,
+        &previous_seatings,
+        groups_count,
+        parse_wind_policy(wind_policy)?,
+        rand_factor,
+    ))
+}
+
+/// Team seating, returns list of (id, rating) tuples, 4 players per table.
+/// $teams is a list of (player id, team id) tuples.
+#[pyfunction]
+#[pyo3(name = "make_team_seating", signature = (players, teams, previous_seatings, wind_policy = "random", rand_factor = 0, strict = false))]
+fn py_make_team_seating(
+    players: PlayersMap,
+    teams: Vec<(u32, u32)>,
+    previous_seatings: Vec<Vec<u32>>,
+    wind_policy: &str,
+    rand_factor: u64,
+    strict: bool,
+) -> PyResult<PlayersMap> {
+    validate_input(&players, &previous_seatings, strict)?;
+    make_team_seating(
+        &players,
+        &teams,
+        &previous_seatings,
+        parse_wind_policy(wind_policy)?,
+        rand_factor,
+    )
+    .map_err(|error| PyValueError::new_err(error.to_string()))
+}
+
+/// Interval seating, returns list of (id, rating) tuples, 4 players per table
+#[pyfunction]
+#[pyo3(name = "make_interval_seating", signature = (players, step, wind_policy = "random", rand_factor = 0, strict = false))]
+fn py_make_interval_seating(
+    players: PlayersMap,
+    step: usize,
+    wind_policy: &str,
+    rand_factor: u64,
+    strict: bool,
+) -> PyResult<PlayersMap> {
+    validate_input(&players, &[], strict)?;
+    if step == 0 {
+        return Err(PyValueError::new_err("step should be positive"));
+    }
+    Ok(make_interval_seating(
+        &players,
+        step,
+        parse_wind_policy(wind_policy)?,
+        rand_factor,
+    ))
+}
+
+/// Intersections table: dict of (player1, player2) -> games played together,
+/// counting previous seatings and the given seating
+#[pyfunction]
+#[pyo3(name = "make_intersections_table", signature = (seating, previous_seatings, strict = false))]
+fn py_make_intersections_table(
+    seating: PlayersMap,
+    previous_seatings: Vec<Vec<u32>>,
+    strict: bool,
+) -> PyResult<HashMap<(u32, u32), u32>> {
+    validate_input(&seating, &previous_seatings, strict)?;
+    Ok(make_intersections_table(&seating, &previous_seatings)
+        .iter()
+        .map(|(player1, player2, count)| ((*player1, *player2), *count))
+        .collect())
+}
+
+/// Quality report: dict with repeats, max_repeats, repeated_pairs (list of
+/// (player1, player2, previous meetings) tuples), rating_variance and max_rating_spread
+#[pyfunction]
+#[pyo3(name = "make_quality_report", signature = (seating, previous_seatings, strict = false))]
+fn py_make_quality_report<'py>(
+    py: Python<'py>,
+    seating: PlayersMap,
+    previous_seatings: Vec<Vec<u32>>,
+    strict: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    validate_input(&seating, &previous_seatings, strict)?;
+    let report = make_quality_report(&seating, &previous_seatings);
+    let dict = PyDict::new(py);
+    dict.set_item("repeats", report.repeats)?;
+    dict.set_item("max_repeats", report.max_repeats)?;
+    dict.set_item("repeated_pairs", report.repeated_pairs)?;
+    dict.set_item("rating_variance", report.rating_variance)?;
+    dict.set_item("max_rating_spread", report.max_rating_spread)?;
+    Ok(dict)
+}
+
+#[pymodule]
+#[pyo3(name = "mahjong_seatings")]
+fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(py_make_swiss_seating, module)?)?;
+    module.add_function(wrap_pyfunction!(py_make_shuffled_seating, module)?)?;
+    module.add_function(wrap_pyfunction!(py_make_team_seating, module)?)?;
+    module.add_function(wrap_pyfunction!(py_make_interval_seating, module)?)?;
+    module.add_function(wrap_pyfunction!(py_make_intersections_table, module)?)?;
+    module.add_function(wrap_pyfunction!(py_make_quality_report, module)?)?;
+    Ok(())
+}
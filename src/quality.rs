@@ -0,0 +1,82 @@
+use crate::interfaces::{PlayersMap, QualityReport};
+use crate::seating_balanced::calc_rating_variance;
+use crate::seating_swiss::make_played_with_matrix;
+
+/// Quality report of seating: previous meetings of players seated at the same tables
+/// and spread of ratings between and within tables.
+/// Seating is expected to be split into tables of 4 players, previous tables should have 4 players.
+pub fn make_quality_report(seating: &PlayersMap, previous_seatings: &[Vec<u32>]) -> QualityReport {
+    let played_with = make_played_with_matrix(seating, previous_seatings);
+    let tables: Vec<PlayersMap> = seating.chunks(4).map(|table| table.to_vec()).collect();
+
+    let mut repeated_pairs = Vec::new();
+    for table in &tables {
+        for i in 0..table.len() {
+            for j in i + 1..table.len() {
+                if let Some(count) = played_with.get_value(table[i].0, table[j].0) {
+                    repeated_pairs.push((table[i].0, table[j].0, count));
+                }
+            }
+        }
+    }
+
+    QualityReport {
+        repeats: repeated_pairs.iter().map(|(_, _, count)| count).sum(),
+        max_repeats: repeated_pairs
+            .iter()
+            .map(|(_, _, count)| *count)
+            .max()
+            .unwrap_or(0),
+        repeated_pairs,
+        rating_variance: calc_rating_variance(&tables),
+        max_rating_spread: tables
+            .iter()
+            .map(|table| {
+                let ratings = table.iter().map(|(_, rating)| *rating);
+                ratings.clone().max().unwrap() - ratings.min().unwrap()
+            })
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_quality_report() {
+        let seating: PlayersMap = vec![
+            (1, 1600),
+            (5, 1500),
+            (2, 1550),
+            (6, 1450),
+            (3, 1400),
+            (7, 1300),
+            (4, 1350),
+            (8, 1250),
+        ];
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![1, 2, 7, 8]];
+
+        assert_eq!(
+            make_quality_report(&seating, &previous_seatings),
+            QualityReport {
+                repeats: 6,
+                max_repeats: 2,
+                repeated_pairs: vec![(1, 2, 2), (5, 6, 1), (3, 4, 1), (7, 8, 2)],
+                rating_variance: 10000.0,
+                max_rating_spread: 150,
+            }
+        );
+        assert_eq!(
+            make_quality_report(&Vec::new(), &[]),
+            QualityReport {
+                repeats: 0,
+                max_repeats: 0,
+                repeated_pairs: Vec::new(),
+                rating_variance: 0.0,
+                max_rating_spread: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::interfaces::{PlayersMap, RenderFormat};
+
+const WINDS: [&str; 4] = ["East", "South", "West", "North"];
+
+/// Part of printable sheet: a titled table
+struct Section {
+    title: String,
+    headers: [&'static str; 3],
+    rows: Vec<[String; 3]>,
+}
+
+/// Printable per-table sheet: players of each table with their winds.
+/// $names is an optional (player_id, name) lookup; players missing there are shown by id.
+pub fn render_table_sheets(
+    seating: &PlayersMap,
+    names: &[(u32, String)],
+    format: RenderFormat,
+) -> String {
+    let sections: Vec<Section> = seating
+        .chunks(4)
+        .enumerate()
+        .map(|(index, table)| Section {
+            title: format!("Table {}", index + 1),
+            headers: ["Wind", "Player", "Id"],
+            rows: table
+                .iter()
+                .enumerate()
+                .map(|(seat, (id, _))| {
+                    [
+                        WINDS[seat].to_string(),
+                        display_name(*id, names),
+                        id.to_string(),
+                    ]
+                })
+                .collect(),
+        })
+        .collect();
+
+    render(&sections, "Seating", format)
+}
+
+/// Printable "find your table" list: players in alphabetical order with their table and wind
+pub fn render_players_list(
+    seating: &PlayersMap,
+    names: &[(u32, String)],
+    format: RenderFormat,
+) -> String {
+    let mut players: Vec<(usize, u32)> = seating
+        .iter()
+        .enumerate()
+        .map(|(seat, (id, _))| (seat, *id))
+        .collect();
+    players.sort_by_key(|(_, id)| sort_key(*id, names));
+
+    let section = Section {
+        title: String::from("Find your table"),
+        headers: ["Player", "Table", "Wind"],
+        rows: players
+            .iter()
+            .map(|(seat, id)| {
+                [
+                    display_name(*id, names),
+                    (seat / 4 + 1).to_string(),
+                    WINDS[seat % 4].to_string(),
+                ]
+            })
+            .collect(),
+    };
+
+    render(&[section], "Find your table", format)
+}
+
+/// Printable itinerary of each player for pre-generated schedule: table and wind in every session.
+/// Players are listed in alphabetical order.
+pub fn render_itineraries(
+    sessions: &[PlayersMap],
+    names: &[(u32, String)],
+    format: RenderFormat,
+) -> String {
+    let mut ids: Vec<u32> = sessions.iter().flatten().map(|(id, _)| *id).collect();
+    ids.sort();
+    ids.dedup();
+    ids.sort_by_key(|id| sort_key(*id, names));
+
+    let sections: Vec<Section> = ids
+        .iter()
+        .map(|id| Section {
+            title: display_name(*id, names),
+            headers: ["Session", "Table", "Wind"],
+            rows: sessions
+                .iter()
+                .enumerate()
+                .map(|(session, seating)| {
+                    match seating.iter().position(|(player, _)| player == id) {
+                        Some(seat) => [
+                            (session + 1).to_string(),
+                            (seat / 4 + 1).to_string(),
+                            WINDS[seat % 4].to_string(),
+                        ],
+                        None => [
+                            (session + 1).to_string(),
+                            String::from("-"),
+                            String::from("-"),
+                        ],
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    render(&sections, "Itineraries", format)
+}
+
+fn display_name(id: u32, names: &[(u32, String)]) -> String {
+    match names.iter().find(|(player, _)| *player == id) {
+        Some((_, name)) => name.clone(),
+        None => id.to_string(),
+    }
+}
+
+/// Named players go first in alphabetical order, then players without name by id
+fn sort_key(id: u32, names: &[(u32, String)]) -> (bool, String, u32) {
+    match names.iter().find(|(player, _)| *player == id) {
+        Some((_, name)) => (false, name.to_lowercase(), id),
+        None => (true, String::new(), id),
+    }
+}
+
+fn render(sections: &[Section], title: &str, format: RenderFormat) -> String {
+    match format {
+        RenderFormat::Html => render_html(sections, title),
+        RenderFormat::Markdown => render_markdown(sections),
+    }
+}
+
+fn render_markdown(sections: &[Section]) -> String {
+    let escape = |text: &str| {
+        text.chars().fold(String::new(), |mut result, c| {
+            if matches!(
+                c,
+                '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#'
+            ) {
+                result.push('\\');
+            }
+            result.push(c);
+            result
+        })
+    };
+
+    let mut result = String::new();
+    for section in sections {
+        result += &format!("## {}\n\n", escape(&section.title));
+        result += &format!("| {} |\n", section.headers.join(" | "));
+        result += "| --- | --- | --- |\n";
+        for row in &section.rows {
+            let cells: Vec<String> = row.iter().map(|cell| escape(cell)).collect();
+            result += &format!("| {} |\n", cells.join(" | "));
+        }
+        result += "\n";
+    }
+    result
+}
+
+fn render_html(sections: &[Section], title: &str) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    };
+
+    let mut result = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\nsection {{ break-inside: avoid; }}\ntable {{ border-collapse: collapse; }}\n\
+         td, th {{ border: 1px solid #000; padding: 4px 12px; }}\n</style>\n</head>\n<body>\n",
+        escape(title)
+    );
+    for section in sections {
+        result += &format!(
+            "<section>\n<h2>{}</h2>\n<table>\n<tr>",
+            escape(&section.title)
+        );
+        for header in section.headers {
+            result += &format!("<th>{header}</th>");
+        }
+        result += "</tr>\n";
+        for row in &section.rows {
+            result += "<tr>";
+            for cell in row {
+                result += &format!("<td>{}</td>", escape(cell));
+            }
+            result += "</tr>\n";
+        }
+        result += "</table>\n</section>\n";
+    }
+    result += "</body>\n</html>\n";
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_seating() -> (PlayersMap, Vec<(u32, String)>) {
+        let seating: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let names = vec![
+            (1, String::from("Zoe")),
+            (2, String::from("adam")),
+            (3, String::from("Bob | Jr")),
+            (5, String::from("<Carol>")),
+        ];
+        (seating, names)
+    }
+
+    #[test]
+    fn test_render_table_sheets() {
+        let (seating, names) = make_seating();
+        let markdown = render_table_sheets(&seating[..4].to_vec(), &names, RenderFormat::Markdown);
+        assert_eq!(
+            markdown,
+            "## Table 1\n\n\
+             | Wind | Player | Id |\n\
+             | --- | --- | --- |\n\
+             | East | Zoe | 1 |\n\
+             | South | adam | 2 |\n\
+             | West | Bob \\| Jr | 3 |\n\
+             | North | 4 | 4 |\n\n"
+        );
+
+        let html = render_table_sheets(&seating, &names, RenderFormat::Html);
+        assert!(html.contains("<h2>Table 2</h2>"));
+        assert!(html.contains("<td>East</td><td>&lt;Carol&gt;</td><td>5</td>"));
+    }
+
+    #[test]
+    fn test_render_players_list() {
+        let (seating, names) = make_seating();
+        let markdown = render_players_list(&seating, &names, RenderFormat::Markdown);
+        let rows: Vec<&str> = markdown.lines().skip(4).take(8).collect();
+        assert_eq!(
+            rows,
+            vec![
+                "| \\<Carol\\> | 2 | East |",
+                "| adam | 1 | South |",
+                "| Bob \\| Jr | 1 | West |",
+                "| Zoe | 1 | East |",
+                "| 4 | 1 | North |",
+                "| 6 | 2 | South |",
+                "| 7 | 2 | West |",
+                "| 8 | 2 | North |",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_itineraries() {
+        let sessions: Vec<PlayersMap> = vec![
+            (1..=8).map(|id| (id, 0)).collect(),
+            vec![(5, 0), (1, 0), (6, 0), (2, 0)],
+        ];
+        let markdown = render_itineraries(&sessions, &[], RenderFormat::Markdown);
+
+        assert!(markdown.starts_with(
+            "## 1\n\n\
+             | Session | Table | Wind |\n\
+             | --- | --- | --- |\n\
+             | 1 | 1 | East |\n\
+             | 2 | 1 | South |\n\n"
+        ));
+        assert!(markdown.contains("## 8\n\n| Session | Table | Wind |\n| --- | --- | --- |\n| 1 | 2 | North |\n| 2 | - | - |\n"));
+    }
+}
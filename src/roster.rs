@@ -0,0 +1,478 @@
+use crate::delimited::{detect_delimiter, find_columns, numbered_lines, split_fields};
+use crate::ema::make_teams;
+use crate::interfaces::PlayersMap;
+use std::fmt;
+
+/// Player of roster spreadsheet
+#[derive(Clone, PartialEq, Debug)]
+pub struct RosterPlayer {
+    pub id: u32,
+    pub name: String,
+    pub rating: i32,
+    pub club: String,
+}
+
+/// Players and previous seatings imported from spreadsheets
+#[derive(Clone, PartialEq, Debug)]
+pub struct Roster {
+    pub players: Vec<RosterPlayer>,
+    pub previous_seatings: Vec<Vec<u32>>, // Tables ordered by session and table, players by seat
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RosterFile {
+    Players,
+    History,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RosterError {
+    pub file: RosterFile,
+    pub line: usize, // 1-based line number, 0 if error is not related to single line
+    pub message: String,
+}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = match self.file {
+            RosterFile::Players => "players",
+            RosterFile::History => "history",
+        };
+        if self.line == 0 {
+            write!(f, "{file}: {}", self.message)
+        } else {
+            write!(f, "{file}, line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for RosterError {}
+
+const PLAYER_COLUMNS: [&str; 4] = ["id", "name", "rating", "club"];
+const HISTORY_COLUMNS: [&str; 4] = ["session", "table", "seat", "player"];
+const WINDS: [&str; 4] = ["east", "south", "west", "north"];
+
+/// One seat of history file
+struct HistoryRow {
+    line: usize,
+    session: u32,
+    table: u32,
+    seat: usize, // 0 - East, 1 - South, 2 - West, 3 - North
+    player: u32,
+}
+
+/// Import roster from CSV or TSV spreadsheets (delimiter is detected by header row).
+/// $players has columns id, rating and optional name and club; $history has columns
+/// session, table, seat (1-4 or East/South/West/North, E/S/W/N) and player, one row per seat,
+/// it may be empty before the first session. Columns may go in any order.
+/// All problems found in both files are reported at once.
+pub fn import_roster(players: &str, history: &str) -> Result<Roster, Vec<RosterError>> {
+    let mut errors = Vec::new();
+    let players = parse_players(players, &mut errors);
+    let rows = parse_history(history, players.as_deref(), &mut errors);
+    let previous_seatings = make_previous_seatings(&rows, &mut errors);
+
+    match (players, errors.is_empty()) {
+        (Some(players), true) => Ok(Roster {
+            players,
+            previous_seatings,
+        }),
+        _ => Err(errors),
+    }
+}
+
+impl Roster {
+    /// Players list to be passed to seating algorithms
+    pub fn players_map(&self) -> PlayersMap {
+        self.players
+            .iter()
+            .map(|player| (player.id, player.rating))
+            .collect()
+    }
+
+    /// (player_id, name) pairs for printable sheets
+    pub fn names(&self) -> Vec<(u32, String)> {
+        self.players
+            .iter()
+            .map(|player| (player.id, player.name.clone()))
+            .collect()
+    }
+
+    /// (player_id, team_id) pairs of players from the same club, to be used in make_team_seating
+    pub fn club_teams(&self) -> Vec<(u32, u32)> {
+        make_teams(
+            self.players
+                .iter()
+                .map(|player| (player.id, player.club.as_str())),
+        )
+    }
+}
+
+/// Parse delimited file into rows with fields ordered as $columns (empty for missing optional columns);
+/// problems are added to $errors. Returns None if header can't be used.
+fn parse_table(
+    text: &str,
+    file: RosterFile,
+    columns: &[&str],
+    required: &[&str],
+    errors: &mut Vec<RosterError>,
+) -> Option<Vec<(usize, Vec<String>)>> {
+    let error = |line: usize, message: String| RosterError {
+        file,
+        line,
+        message,
+    };
+    let mut lines = numbered_lines(text);
+    let (header_line, header) = lines.next()?;
+    let delimiter = detect_delimiter(header);
+
+    let Some(header) = split_fields(header, delimiter) else {
+        errors.push(error(
+            header_line,
+            String::from("unterminated quoted field"),
+        ));
+        return None;
+    };
+    let positions = find_columns(&header, columns);
+    let missing: Vec<&&str> = columns
+        .iter()
+        .zip(&positions)
+        .filter(|(column, position)| position.is_none() && required.contains(column))
+        .map(|(column, _)| column)
+        .collect();
+    if !missing.is_empty() {
+        for column in missing {
+            errors.push(error(header_line, format!("missing column '{column}'")));
+        }
+        return None;
+    }
+
+    let mut rows = Vec::new();
+    for (line, text) in lines {
+        match split_fields(text, delimiter) {
+            None => errors.push(error(line, String::from("unterminated quoted field"))),
+            Some(fields) if fields.len() != header.len() => errors.push(error(
+                line,
+                format!("expected {} fields, got {}", header.len(), fields.len()),
+            )),
+            Some(fields) => rows.push((
+                line,
+                positions
+                    .iter()
+                    .map(|position| position.map(|p| fields[p].clone()).unwrap_or_default())
+                    .collect(),
+            )),
+        }
+    }
+    Some(rows)
+}
+
+fn parse_players(text: &str, errors: &mut Vec<RosterError>) -> Option<Vec<RosterPlayer>> {
+    let error = |line: usize, message: String| RosterError {
+        file: RosterFile::Players,
+        line,
+        message,
+    };
+    if numbered_lines(text).next().is_none() {
+        errors.push(error(0, String::from("file is empty")));
+        return None;
+    }
+    let rows = parse_table(
+        text,
+        RosterFile::Players,
+        &PLAYER_COLUMNS,
+        &["id", "rating"],
+        errors,
+    )?;
+
+    let mut players: Vec<(usize, RosterPlayer)> = Vec::new();
+    for (line, fields) in rows {
+        let id = match fields[0].parse::<u32>() {
+            Ok(id) if id > 0 => Some(id),
+            _ => {
+                errors.push(error(line, format!("invalid id: '{}'", fields[0])));
+                None
+            }
+        };
+        let rating = match fields[2].parse::<i32>() {
+            Ok(rating) => Some(rating),
+            Err(_) => {
+                errors.push(error(line, format!("invalid rating: '{}'", fields[2])));
+                None
+            }
+        };
+        let (Some(id), Some(rating)) = (id, rating) else {
+            continue;
+        };
+
+        if let Some((first_line, _)) = players.iter().find(|(_, player)| player.id == id) {
+            errors.push(error(
+                line,
+                format!("duplicate player id {id}, first defined at line {first_line}"),
+            ));
+            continue;
+        }
+        players.push((
+            line,
+            RosterPlayer {
+                id,
+                name: fields[1].clone(),
+                rating,
+                club: fields[3].clone(),
+            },
+        ));
+    }
+
+    Some(players.into_iter().map(|(_, player)| player).collect())
+}
+
+/// Parse seat as 1-4 or wind name
+fn parse_seat(seat: &str) -> Option<usize> {
+    let seat = seat.to_lowercase();
+    match seat.parse::<usize>() {
+        Ok(number) if (1..=4).contains(&number) => Some(number - 1),
+        Ok(_) => None,
+        Err(_) => WINDS
+            .iter()
+            .position(|wind| *wind == seat || wind[..1] == seat),
+    }
+}
+
+/// Parse history rows; players are checked against $players if players file was parsed
+fn parse_history(
+    text: &str,
+    players: Option<&[RosterPlayer]>,
+    errors: &mut Vec<RosterError>,
+) -> Vec<HistoryRow> {
+    let error = |line: usize, message: String| RosterError {
+        file: RosterFile::History,
+        line,
+        message,
+    };
+    let Some(rows) = parse_table(
+        text,
+        RosterFile::History,
+        &HISTORY_COLUMNS,
+        &HISTORY_COLUMNS,
+        errors,
+    ) else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<HistoryRow> = Vec::new();
+    for (line, fields) in rows {
+        let mut parse_number = |column: usize| match fields[column].parse::<u32>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                errors.push(error(
+                    line,
+                    format!("invalid {}: '{}'", HISTORY_COLUMNS[column], fields[column]),
+                ));
+                None
+            }
+        };
+        let (session, table, player) = (parse_number(0), parse_number(1), parse_number(3));
+        let seat = parse_seat(&fields[2]);
+        if seat.is_none() {
+            errors.push(error(line, format!("invalid seat: '{}'", fields[2])));
+        }
+        let (Some(session), Some(table), Some(seat), Some(player)) = (session, table, seat, player)
+        else {
+            continue;
+        };
+
+        if let Some(other) = result
+            .iter()
+            .find(|row| row.session == session && row.table == table && row.seat == seat)
+        {
+            errors.push(error(
+                line,
+                format!(
+                    "seat {} of session {session}, table {table} is already taken at line {}",
+                    fields[2], other.line
+                ),
+            ));
+            continue;
+        }
+        if let Some(other) = result
+            .iter()
+            .find(|row| row.session == session && row.player == player)
+        {
+            errors.push(error(
+                line,
+                format!(
+                    "player {player} is already seated in session {session} at line {}",
+                    other.line
+                ),
+            ));
+        }
+        // Rows with unknown or repeated players still take their seats,
+        // so table and session sizes are not reported as broken because of them
+        if players.is_some_and(|players| !players.iter().any(|p| p.id == player)) {
+            errors.push(error(line, format!("unknown player {player}")));
+        }
+
+        result.push(HistoryRow {
+            line,
+            session,
+            table,
+            seat,
+            player,
+        });
+    }
+    result
+}
+
+/// Group history rows into tables, checking tables and sessions sizes
+fn make_previous_seatings(rows: &[HistoryRow], errors: &mut Vec<RosterError>) -> Vec<Vec<u32>> {
+    let error = |line: usize, message: String| RosterError {
+        file: RosterFile::History,
+        line,
+        message,
+    };
+    let mut keys: Vec<(u32, u32)> = rows.iter().map(|row| (row.session, row.table)).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut previous_seatings = Vec::new();
+    for (session, table) in &keys {
+        let mut table_rows: Vec<&HistoryRow> = rows
+            .iter()
+            .filter(|row| row.session == *session && row.table == *table)
+            .collect();
+        if table_rows.len() != 4 {
+            errors.push(error(
+                table_rows[0].line,
+                format!(
+                    "session {session}, table {table} has {} players instead of 4",
+                    table_rows.len()
+                ),
+            ));
+        }
+        table_rows.sort_by_key(|row| row.seat);
+        previous_seatings.push(table_rows.iter().map(|row| row.player).collect());
+    }
+
+    // All sessions should have the same tables count as the first one
+    let mut sessions: Vec<u32> = keys.iter().map(|(session, _)| *session).collect();
+    sessions.dedup();
+    let tables_count = |session: u32| keys.iter().filter(|(s, _)| *s == session).count();
+    if let Some(first) = sessions.first() {
+        for session in &sessions[1..] {
+            if tables_count(*session) != tables_count(*first) {
+                let line = rows
+                    .iter()
+                    .find(|row| row.session == *session)
+                    .unwrap()
+                    .line;
+                errors.push(error(
+                    line,
+                    format!(
+                        "session {session} has {} table(s), while session {first} has {}",
+                        tables_count(*session),
+                        tables_count(*first)
+                    ),
+                ));
+            }
+        }
+    }
+
+    previous_seatings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::WindPolicy;
+    use crate::seating_swiss::make_swiss_seating;
+
+    const PLAYERS: &str = include_str!("../tests/fixtures/roster_players.csv");
+    const HISTORY: &str = include_str!("../tests/fixtures/roster_history.tsv");
+
+    #[test]
+    fn test_import_roster() {
+        let roster = import_roster(PLAYERS, HISTORY).unwrap();
+
+        assert_eq!(roster.players.len(), 8);
+        assert_eq!(roster.names()[0], (1, String::from("Tanaka, Hiro")));
+        assert_eq!(roster.players_map()[1], (2, 1600));
+        assert_eq!(
+            roster.previous_seatings,
+            vec![
+                vec![1, 2, 3, 4],
+                vec![5, 6, 7, 8],
+                vec![8, 1, 4, 5],
+                vec![2, 3, 6, 7],
+            ]
+        );
+        assert_eq!(
+            roster.club_teams(),
+            vec![(1, 1), (2, 2), (4, 3), (5, 2), (7, 1)]
+        );
+
+        let seating = make_swiss_seating(
+            &roster.players_map(),
+            &roster.previous_seatings,
+            WindPolicy::Fixed,
+            12345,
+        );
+        assert_eq!(seating.len(), 8);
+
+        // History is optional before the first session
+        let roster = import_roster(PLAYERS, "").unwrap();
+        assert!(roster.previous_seatings.is_empty());
+    }
+
+    #[test]
+    fn test_import_roster_errors() {
+        let players = format!("{PLAYERS}9,Extra,1400,\n9,Again,1400,\n").replace("1500", "15OO");
+        let history = format!("{HISTORY}3\t1\tE\t1\n3\t1\tS\t2\n3\t1\tW\t3\n3\t1\tX\t4\n")
+            .replace("1\t2\t3\t7", "1\t2\t3\t42")
+            .replace("2\t2\tW\t6", "2\t2\tW\t1")
+            .replace("2\t1\tN\t5\n", "");
+
+        let errors: Vec<String> = import_roster(&players, &history)
+            .unwrap_err()
+            .iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "players, line 7: invalid rating: '15OO'",
+                "players, line 11: duplicate player id 9, first defined at line 10",
+                "history, line 7: unknown player 6",
+                "history, line 8: unknown player 42",
+                "history, line 15: player 1 is already seated in session 2 at line 12",
+                "history, line 20: invalid seat: 'X'",
+                "history, line 14: session 2, table 1 has 3 players instead of 4",
+                "history, line 17: session 3, table 1 has 3 players instead of 4",
+                "history, line 17: session 3 has 1 table(s), while session 1 has 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_roster_header_errors() {
+        let errors = import_roster("id,name\n1,A\n", "session,table,player\n").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                RosterError {
+                    file: RosterFile::Players,
+                    line: 1,
+                    message: String::from("missing column 'rating'")
+                },
+                RosterError {
+                    file: RosterFile::History,
+                    line: 1,
+                    message: String::from("missing column 'seat'")
+                },
+            ]
+        );
+        assert_eq!(
+            import_roster("", "").unwrap_err()[0].to_string(),
+            "players: file is empty"
+        );
+    }
+}
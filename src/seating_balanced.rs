@@ -0,0 +1,189 @@
+use crate::interfaces::{BalancedSeating, PlayersMap, WindPolicy};
+use crate::matrix::Matrix;
+use crate::seating_snake::{count_table_repeats, make_snake_tables, SnakeError};
+use crate::seating_swiss::make_played_with_matrix;
+use crate::winds::apply_wind_policy;
+
+/// Balanced-strength seating optimizer
+/// Starts from snake seating and swaps players between tables while weighted sum of
+/// table average rating variance and count of repeated pairs decreases.
+/// Both components of the resulting seating are reported along with it.
+/// Returns error if players count is not a multiple of 4.
+pub fn make_balanced_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    rating_weight: f64,
+    repeats_weight: f64,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<BalancedSeating, SnakeError> {
+    const MAX_PASSES: usize = 100;
+
+    let played_with = make_played_with_matrix(players_map, previous_seatings);
+    let mut tables = make_snake_tables(players_map)?;
+    let objective = |tables: &[PlayersMap]| {
+        rating_weight * calc_rating_variance(tables)
+            + repeats_weight * calc_repeats(tables, &played_with) as f64
+    };
+
+    let mut best_objective = objective(&tables);
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for table1 in 0..tables.len() {
+            for table2 in table1 + 1..tables.len() {
+                for seat1 in 0..4 {
+                    for seat2 in 0..4 {
+                        swap_players(&mut tables, (table1, seat1), (table2, seat2));
+                        let new_objective = objective(&tables);
+                        if new_objective < best_objective {
+                            best_objective = new_objective;
+                            improved = true;
+                        } else {
+                            swap_players(&mut tables, (table1, seat1), (table2, seat2));
+                        }
+                    }
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    let rating_variance = calc_rating_variance(&tables);
+    let repeats = calc_repeats(&tables, &played_with);
+    let flattened_groups: PlayersMap = tables.into_iter().flatten().collect();
+
+    Ok(BalancedSeating {
+        seating: apply_wind_policy(
+            &flattened_groups,
+            previous_seatings,
+            wind_policy,
+            rand_factor,
+        ),
+        rating_variance,
+        repeats,
+    })
+}
+
+fn swap_players(tables: &mut [PlayersMap], first: (usize, usize), second: (usize, usize)) {
+    let player = tables[first.0][first.1];
+    tables[first.0][first.1] = tables[second.0][second.1];
+    tables[second.0][second.1] = player;
+}
+
+/// Variance of average ratings of the tables
+pub(crate) fn calc_rating_variance(tables: &[PlayersMap]) -> f64 {
+    if tables.is_empty() {
+        return 0.0;
+    }
+
+    let averages: Vec<f64> = tables
+        .iter()
+        .map(|table| table.iter().map(|(_, rating)| *rating as f64).sum::<f64>() / 4.0)
+        .collect();
+    let mean = averages.iter().sum::<f64>() / averages.len() as f64;
+
+    averages
+        .iter()
+        .map(|average| (average - mean) * (average - mean))
+        .sum::<f64>()
+        / averages.len() as f64
+}
+
+fn calc_repeats(tables: &[PlayersMap], played_with: &Matrix<u32>) -> u32 {
+    tables
+        .iter()
+        .map(|table| count_table_repeats(table, played_with))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_rating_variance() {
+        let tables = vec![
+            vec![(1, 1600), (2, 1600), (3, 1600), (4, 1600)],
+            vec![(5, 1400), (6, 1400), (7, 1400), (8, 1400)],
+        ];
+        assert_eq!(calc_rating_variance(&tables), 10000.0);
+    }
+
+    #[test]
+    fn test_make_balanced_seating_without_history() {
+        let players = vec![
+            (1, 1800),
+            (2, 1700),
+            (3, 1600),
+            (4, 1500),
+            (5, 1400),
+            (6, 1300),
+            (7, 1200),
+            (8, 1000),
+        ];
+
+        let result =
+            make_balanced_seating(&players, &[], 1.0, 1.0, WindPolicy::Random, 12345).unwrap();
+        assert_eq!(result.seating.len(), 8);
+        assert_eq!(result.repeats, 0);
+        // Best split is 5700 vs 5800 total rating, table averages differ by 25
+        assert_eq!(result.rating_variance, 156.25);
+    }
+
+    #[test]
+    fn test_make_balanced_seating_with_history() {
+        let players = vec![
+            (1, 1509),
+            (2, 1508),
+            (3, 1507),
+            (4, 1506),
+            (5, 1505),
+            (6, 1504),
+            (7, 1503),
+            (8, 1502),
+            (9, 1501),
+            (10, 1500),
+            (11, 1499),
+            (12, 1498),
+            (13, 1497),
+            (14, 1496),
+            (15, 1495),
+            (16, 1494),
+        ];
+
+        let previous_seatings = vec![
+            vec![1, 8, 9, 16],
+            vec![2, 7, 10, 15],
+            vec![3, 6, 11, 14],
+            vec![4, 5, 12, 13],
+        ];
+
+        let balanced_only = make_balanced_seating(
+            &players,
+            &previous_seatings,
+            1.0,
+            0.0,
+            WindPolicy::Random,
+            12345,
+        )
+        .unwrap();
+        assert_eq!(balanced_only.rating_variance, 0.0);
+        assert_eq!(balanced_only.repeats, 24);
+
+        let result = make_balanced_seating(
+            &players,
+            &previous_seatings,
+            1.0,
+            100.0,
+            WindPolicy::Random,
+            12345,
+        )
+        .unwrap();
+        assert_eq!(result.repeats, 0);
+        assert!(result.rating_variance < 1.0);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::interfaces::{FinalsGrouping, FinalsWinds, PlayersMap};
+use crate::seating_snake::split_to_snake_tables;
+
+/// Final tables / playoff seating
+/// Takes players list ordered by standings (top player first) and seats $advancing top players
+/// to final tables. Advancing players count is truncated to be divisible by 4.
+/// Winds are not random: they are assigned by standing according to $winds rule.
+pub fn make_finals_seating(
+    standings: &PlayersMap,
+    advancing: usize,
+    grouping: FinalsGrouping,
+    winds: FinalsWinds,
+) -> PlayersMap {
+    let advancing = 4 * (advancing.min(standings.len()) / 4);
+    let finalists = &standings[..advancing];
+
+    let mut tables: Vec<PlayersMap> = match grouping {
+        FinalsGrouping::Straight => finalists.chunks(4).map(|table| table.to_vec()).collect(),
+        FinalsGrouping::Snake => split_to_snake_tables(finalists),
+    };
+
+    // Players at each table are already ordered by standing
+    if winds == FinalsWinds::ReverseStanding {
+        for table in &mut tables {
+            table.reverse();
+        }
+    }
+
+    tables.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_standings() -> PlayersMap {
+        vec![
+            (11, 52000),
+            (12, 48000),
+            (13, 31000),
+            (14, 30000),
+            (15, 22000),
+            (16, 18000),
+            (17, 12000),
+            (18, 9000),
+            (19, 5000),
+            (20, 1000),
+        ]
+    }
+
+    #[test]
+    fn test_make_finals_seating_top4() {
+        let seating = make_finals_seating(
+            &make_standings(),
+            4,
+            FinalsGrouping::Straight,
+            FinalsWinds::ByStanding,
+        );
+        assert_eq!(
+            seating,
+            vec![(11, 52000), (12, 48000), (13, 31000), (14, 30000)]
+        );
+    }
+
+    #[test]
+    fn test_make_finals_seating_snake() {
+        let seating = make_finals_seating(
+            &make_standings(),
+            8,
+            FinalsGrouping::Snake,
+            FinalsWinds::ByStanding,
+        );
+        assert_eq!(
+            seating,
+            vec![
+                (11, 52000),
+                (14, 30000),
+                (15, 22000),
+                (18, 9000),
+                (12, 48000),
+                (13, 31000),
+                (16, 18000),
+                (17, 12000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_finals_seating_reverse_winds() {
+        // 10 advancing players are truncated to 2 full tables
+        let seating = make_finals_seating(
+            &make_standings(),
+            10,
+            FinalsGrouping::Straight,
+            FinalsWinds::ReverseStanding,
+        );
+        assert_eq!(
+            seating,
+            vec![
+                (14, 30000),
+                (13, 31000),
+                (12, 48000),
+                (11, 52000),
+                (18, 9000),
+                (17, 12000),
+                (16, 18000),
+                (15, 22000),
+            ]
+        );
+    }
+}
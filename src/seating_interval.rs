@@ -1,5 +1,10 @@
-use crate::interfaces::{PlayersMap, TableWithRating};
-use crate::shuffle::update_places_to_random;
+use crate::interfaces::{
+    IntervalConfig, IntervalSegment, PlayersMap, RemainderPlacement, TableWithRating, WindPolicy,
+};
+use crate::matrix::Matrix;
+use crate::seating_snake::count_table_repeats;
+use crate::seating_swiss::make_played_with_matrix;
+use crate::winds::apply_wind_policy;
 
 /// Make interval seating
 /// Players from the top are seating with interval of $step, but if table count is
@@ -7,70 +12,209 @@ use crate::shuffle::update_places_to_random;
 pub fn make_interval_seating(
     current_rating_list: &PlayersMap,
     step: usize,
+    wind_policy: WindPolicy,
     rand_factor: u64,
 ) -> PlayersMap {
-    let mut tables = Vec::new();
-    let mut current_table = Vec::new();
+    make_interval_seating_with_history(current_rating_list, &[], step, 0, wind_policy, rand_factor)
+}
 
-    // These guys from bottom could not be placed with desired interval, so they play with interval 1
-    let players_to_seat_with_no_interval = 4 * ((current_rating_list.len() / 4) % step);
-    // These guys from top should be placed as required
-    let players_possible_to_seat_with_interval =
-        current_rating_list.len() - players_to_seat_with_no_interval;
+/// Make interval seating which respects previous meetings
+/// Same as interval seating, but inside each group of players seated with the same interval
+/// players may be swapped between tables if it reduces count of repeated pairs.
+/// No player is moved more than $max_displacement places away from their rank in the group.
+pub fn make_interval_seating_with_history(
+    current_rating_list: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    step: usize,
+    max_displacement: usize,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> PlayersMap {
+    let config = IntervalConfig {
+        segments: vec![IntervalSegment { tables: 0, step }],
+        remainder: RemainderPlacement::Bottom,
+        max_displacement,
+    };
+    make_configured_interval_seating(
+        current_rating_list,
+        previous_seatings,
+        &config,
+        wind_policy,
+        rand_factor,
+    )
+}
 
-    // Fill tables with interval of $step
-    for offset in 0..step {
-        let mut i = offset;
-        while i < players_possible_to_seat_with_interval {
-            current_table.push(current_rating_list[i]);
-            if current_table.len() == 4 {
-                let max_rating = current_table
-                    .iter()
-                    .map(|(_, rating)| *rating)
-                    .max()
-                    .unwrap_or(-1000000);
-                tables.push(TableWithRating {
-                    players: current_table.clone(),
-                    max_rating,
-                });
-                current_table.clear();
-            }
-            i += step;
+/// Make interval seating with custom configuration
+/// Rating list is split into segments from the top, each segment is seated with its own interval.
+/// Players not covered by any segment are seated with step 1.
+/// See RemainderPlacement for composition of tables when segment tables count is not divisible by step.
+pub fn make_configured_interval_seating(
+    current_rating_list: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    config: &IntervalConfig,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> PlayersMap {
+    let mut groups: Vec<PlayersMap> = Vec::new();
+    let mut segment_start = 0;
+    for segment in &config.segments {
+        let players_left = current_rating_list.len() - segment_start;
+        let segment_size = if segment.tables == 0 {
+            players_left
+        } else {
+            players_left.min(4 * segment.tables)
+        };
+        let segment_players = &current_rating_list[segment_start..segment_start + segment_size];
+        groups.extend(make_interval_groups(
+            segment_players,
+            segment.step,
+            config.remainder,
+        ));
+        segment_start += segment_size;
+    }
+
+    // Players out of any segment are seated with interval 1
+    groups.push(current_rating_list[segment_start..].to_vec());
+
+    if !previous_seatings.is_empty() && config.max_displacement > 0 {
+        let played_with = make_played_with_matrix(current_rating_list, previous_seatings);
+        for group in &mut groups {
+            reduce_repeats_in_group(group, &played_with, config.max_displacement);
         }
     }
 
-    // Fill rest of tables with interval 1
-    for i in players_possible_to_seat_with_interval..current_rating_list.len() {
-        current_table.push(current_rating_list[i]);
-        if current_table.len() == 4 {
-            let max_rating = current_table
+    let mut tables = Vec::new();
+    for group in groups {
+        for table in group.chunks(4) {
+            let max_rating = table
                 .iter()
                 .map(|(_, rating)| *rating)
                 .max()
                 .unwrap_or(-1000000);
             tables.push(TableWithRating {
-                players: current_table.clone(),
+                players: table.to_vec(),
                 max_rating,
             });
-            current_table.clear();
         }
     }
 
     // Sort tables by top player score
-    tables.sort_by(|a, b| b.max_rating.cmp(&a.max_rating));
+    tables.sort_by_key(|table| std::cmp::Reverse(table.max_rating));
 
     let mut flattened_groups = Vec::new();
     for table in tables {
         flattened_groups.extend(table.players);
     }
 
-    update_places_to_random(&flattened_groups, rand_factor)
+    apply_wind_policy(
+        &flattened_groups,
+        previous_seatings,
+        wind_policy,
+        rand_factor,
+    )
+}
+
+/// Split players into groups of players seated with the same interval.
+/// Each group is seated to tables in order, 4 players per table.
+fn make_interval_groups(
+    players: &[(u32, i32)],
+    step: usize,
+    remainder: RemainderPlacement,
+) -> Vec<PlayersMap> {
+    let tables_count = players.len() / 4;
+    // These guys could not be placed with desired interval, so they play with interval 1
+    let players_to_seat_with_no_interval = 4 * (tables_count % step);
+    let remainder_start = match remainder {
+        RemainderPlacement::Top => 0,
+        RemainderPlacement::Middle => 4 * ((tables_count - tables_count % step) / 2),
+        RemainderPlacement::Bottom => players.len() - players_to_seat_with_no_interval,
+    };
+    let remainder_end = remainder_start + players_to_seat_with_no_interval;
+
+    // These guys should be placed as required
+    let players_to_seat_with_interval: PlayersMap = players[..remainder_start]
+        .iter()
+        .chain(players[remainder_end..].iter())
+        .copied()
+        .collect();
+
+    let mut groups: Vec<PlayersMap> = Vec::new();
+    for offset in 0..step {
+        groups.push(
+            players_to_seat_with_interval
+                .iter()
+                .skip(offset)
+                .step_by(step)
+                .copied()
+                .collect(),
+        );
+    }
+    groups.push(players[remainder_start..remainder_end].to_vec());
+
+    groups
+}
+
+/// Swap players of different tables in the group while it decreases count of repeated pairs.
+/// Each player should stay within $max_displacement places from their initial rank in the group.
+fn reduce_repeats_in_group(
+    group: &mut PlayersMap,
+    played_with: &Matrix<u32>,
+    max_displacement: usize,
+) {
+    const MAX_PASSES: usize = 10;
+
+    // Initial rank of player at each place of the group
+    let mut ranks: Vec<usize> = (0..group.len()).collect();
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for i in 0..group.len() {
+            for j in i + 1..(i + max_displacement + 1).min(group.len()) {
+                if i / 4 == j / 4
+                    || ranks[i].abs_diff(j) > max_displacement
+                    || ranks[j].abs_diff(i) > max_displacement
+                {
+                    continue;
+                }
+
+                let (table1, table2) = (i / 4 * 4, j / 4 * 4);
+                let before = count_table_repeats(&group[table1..table1 + 4], played_with)
+                    + count_table_repeats(&group[table2..table2 + 4], played_with);
+                group.swap(i, j);
+                let after = count_table_repeats(&group[table1..table1 + 4], played_with)
+                    + count_table_repeats(&group[table2..table2 + 4], played_with);
+
+                if after < before {
+                    ranks.swap(i, j);
+                    improved = true;
+                } else {
+                    group.swap(i, j);
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sorted_tables(seating: &PlayersMap) -> Vec<Vec<u32>> {
+        seating
+            .chunks(4)
+            .map(|table| {
+                let mut ids: Vec<u32> = table.iter().map(|(id, _)| *id).collect();
+                ids.sort();
+                ids
+            })
+            .collect()
+    }
+
     #[test]
     fn test_make_interval_seating_step1() {
         let players = vec![
@@ -92,7 +236,7 @@ mod tests {
             (16, 1494),
         ];
 
-        let seating = make_interval_seating(&players, 1, 12345);
+        let seating = make_interval_seating(&players, 1, WindPolicy::Random, 12345);
 
         assert_eq!(
             seating,
@@ -138,7 +282,7 @@ mod tests {
             (16, 1494),
         ];
 
-        let seating = make_interval_seating(&players, 2, 12345);
+        let seating = make_interval_seating(&players, 2, WindPolicy::Random, 12345);
 
         assert_eq!(
             seating,
@@ -184,7 +328,7 @@ mod tests {
             (16, 1494),
         ];
 
-        let seating = make_interval_seating(&players, 3, 12345);
+        let seating = make_interval_seating(&players, 3, WindPolicy::Random, 12345);
 
         assert_eq!(
             seating,
@@ -230,7 +374,7 @@ mod tests {
             (16, 1494),
         ];
 
-        let seating = make_interval_seating(&players, 4, 12345);
+        let seating = make_interval_seating(&players, 4, WindPolicy::Random, 12345);
 
         assert_eq!(
             seating,
@@ -254,4 +398,164 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_make_interval_seating_with_history() {
+        let players = vec![
+            (1, 1508),
+            (2, 1507),
+            (3, 1506),
+            (4, 1505),
+            (5, 1504),
+            (6, 1503),
+            (7, 1502),
+            (8, 1501),
+        ];
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+
+        // No displacement allowed: plain interval seating
+        let seating = make_interval_seating_with_history(
+            &players,
+            &previous_seatings,
+            1,
+            0,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]]
+        );
+
+        // Players of adjacent ranks on the table border are swapped
+        let seating = make_interval_seating_with_history(
+            &players,
+            &previous_seatings,
+            1,
+            1,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![vec![1, 2, 3, 5], vec![4, 6, 7, 8]]
+        );
+    }
+
+    fn make_rating_list(players_count: u32) -> PlayersMap {
+        (1..=players_count)
+            .map(|id| (id, 2000 - id as i32))
+            .collect()
+    }
+
+    #[test]
+    fn test_make_configured_interval_seating_remainder_top() {
+        let config = IntervalConfig {
+            segments: vec![IntervalSegment { tables: 0, step: 2 }],
+            remainder: RemainderPlacement::Top,
+            max_displacement: 0,
+        };
+        let seating = make_configured_interval_seating(
+            &make_rating_list(20),
+            &[],
+            &config,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![
+                vec![1, 2, 3, 4],
+                vec![5, 7, 9, 11],
+                vec![6, 8, 10, 12],
+                vec![13, 15, 17, 19],
+                vec![14, 16, 18, 20],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_configured_interval_seating_remainder_middle() {
+        let config = IntervalConfig {
+            segments: vec![IntervalSegment { tables: 0, step: 2 }],
+            remainder: RemainderPlacement::Middle,
+            max_displacement: 0,
+        };
+        let seating = make_configured_interval_seating(
+            &make_rating_list(20),
+            &[],
+            &config,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![
+                vec![1, 3, 5, 7],
+                vec![2, 4, 6, 8],
+                vec![9, 10, 11, 12],
+                vec![13, 15, 17, 19],
+                vec![14, 16, 18, 20],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_configured_interval_seating_remainder_bottom() {
+        let config = IntervalConfig {
+            segments: vec![IntervalSegment { tables: 0, step: 2 }],
+            remainder: RemainderPlacement::Bottom,
+            max_displacement: 0,
+        };
+        let seating = make_configured_interval_seating(
+            &make_rating_list(20),
+            &[],
+            &config,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![
+                vec![1, 3, 5, 7],
+                vec![2, 4, 6, 8],
+                vec![9, 11, 13, 15],
+                vec![10, 12, 14, 16],
+                vec![17, 18, 19, 20],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_configured_interval_seating_segments() {
+        // Top 4 tables are seated with step 2, next 3 tables with step 3, the rest with step 1
+        let config = IntervalConfig {
+            segments: vec![
+                IntervalSegment { tables: 4, step: 2 },
+                IntervalSegment { tables: 3, step: 3 },
+            ],
+            remainder: RemainderPlacement::Bottom,
+            max_displacement: 0,
+        };
+        let seating = make_configured_interval_seating(
+            &make_rating_list(32),
+            &[],
+            &config,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![
+                vec![1, 3, 5, 7],
+                vec![2, 4, 6, 8],
+                vec![9, 11, 13, 15],
+                vec![10, 12, 14, 16],
+                vec![17, 20, 23, 26],
+                vec![18, 21, 24, 27],
+                vec![19, 22, 25, 28],
+                vec![29, 30, 31, 32],
+            ]
+        );
+    }
 }
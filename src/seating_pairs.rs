@@ -0,0 +1,277 @@
+use crate::interfaces::PlayersMap;
+use crate::matrix::Matrix;
+use crate::minimap::Minimap;
+use crate::seating_shuffle::find_best_shuffle;
+use lcg_rand::rand::LCG;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum PairsError {
+    OddPairsCount { count: usize }, // Each table holds two pairs
+    UnknownPlayer { id: u32 },      // Player of pair is missing in players list
+    SelfPair { id: u32 },           // Player is paired with themselves
+    DuplicatePlayer { id: u32 },    // Player is listed in more than one pair
+}
+
+impl fmt::Display for PairsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairsError::OddPairsCount { count } => {
+                write!(f, "pairs count should be even, got {count}")
+            }
+            PairsError::UnknownPlayer { id } => write!(f, "player {id} of pairs is unknown"),
+            PairsError::SelfPair { id } => write!(f, "player {id} is paired with themselves"),
+            PairsError::DuplicatePlayer { id } => {
+                write!(f, "player {id} is listed in more than one pair")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PairsError {}
+
+/// Pairs (doubles) seating
+/// $pairs is a list of (player_id, partner_id) tuples; players out of pairs are not seated.
+/// Each table holds two pairs, and partners always sit opposite: one pair takes East and West,
+/// another pair takes South and North. Pairs are placed with shuffle optimizer to minimize
+/// count of repeated meetings of the same pairs.
+/// Returns error if pairs count is odd, any player of pairs is missing in $players_map,
+/// paired with themselves or listed in more than one pair.
+pub fn make_pairs_seating(
+    players_map: &PlayersMap,
+    pairs: &[(u32, u32)],
+    previous_seatings: &[Vec<u32>],
+    rand_factor: u64,
+) -> Result<PlayersMap, PairsError> {
+    if !pairs.len().is_multiple_of(2) {
+        return Err(PairsError::OddPairsCount { count: pairs.len() });
+    }
+    if pairs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut player_to_rating: Minimap<i32> = Minimap::new(players_map.len());
+    player_to_rating.fill_with(players_map);
+    for (player, partner) in pairs {
+        for id in [*player, *partner] {
+            if player_to_rating.get_value(id).is_none() {
+                return Err(PairsError::UnknownPlayer { id });
+            }
+        }
+    }
+    let mut player_to_pair: Minimap<u32> = Minimap::new(players_map.len());
+    for (index, (player, partner)) in pairs.iter().enumerate() {
+        if player == partner {
+            return Err(PairsError::SelfPair { id: *player });
+        }
+        for id in [*player, *partner] {
+            if player_to_pair.get_value(id).is_some() {
+                return Err(PairsError::DuplicatePlayer { id });
+            }
+            player_to_pair.set_value(id, index as u32 + 1);
+        }
+    }
+
+    // Each pair is seated by shuffle optimizer as a single player with summary rating
+    let pairs_map: PlayersMap = pairs
+        .iter()
+        .enumerate()
+        .map(|(index, (player, partner))| {
+            (
+                index as u32 + 1,
+                player_to_rating.get_value(*player).unwrap()
+                    + player_to_rating.get_value(*partner).unwrap(),
+            )
+        })
+        .collect();
+
+    let pair_seatings: Vec<Vec<u32>> = previous_seatings
+        .iter()
+        .map(|table| {
+            let mut pair_ids: Vec<u32> = table
+                .iter()
+                .filter_map(|id| player_to_pair.get_value(*id))
+                .collect();
+            pair_ids.sort();
+            pair_ids.dedup();
+            pair_ids
+        })
+        .collect();
+    let tables_count = pairs.len() / 2;
+    let last_session_start = pair_seatings.len().saturating_sub(tables_count);
+
+    let pair_played_with = make_pair_played_with_matrix(pairs.len(), &pair_seatings);
+    let last_session_played_with =
+        make_pair_played_with_matrix(pairs.len(), &pair_seatings[last_session_start..]);
+
+    let best_seating = find_best_shuffle(&pairs_map, 1, rand_factor, |seating| {
+        calculate_pairs_factor(seating, &pair_played_with, &last_session_played_with)
+    });
+
+    // Partners sit opposite, random pair takes East
+    let mut random: LCG = LCG::from_seed(rand_factor);
+    let mut result_seating = Vec::new();
+    for table in best_seating.chunks(2) {
+        let (first, second) = if random.next().is_multiple_of(2) {
+            (table[0].0, table[1].0)
+        } else {
+            (table[1].0, table[0].0)
+        };
+        let (player1, partner1) = pairs[first as usize - 1];
+        let (player2, partner2) = pairs[second as usize - 1];
+        for id in [player1, player2, partner1, partner2] {
+            result_seating.push((id, player_to_rating.get_value(id).unwrap()));
+        }
+    }
+
+    Ok(result_seating)
+}
+
+/// Count previous meetings of each two pairs
+fn make_pair_played_with_matrix(pairs_count: usize, pair_seatings: &[Vec<u32>]) -> Matrix<u32> {
+    let mut pair_played_with = Matrix::new(pairs_count);
+
+    for table in pair_seatings {
+        for i in 0..table.len() {
+            for j in i + 1..table.len() {
+                pair_played_with.set_value(
+                    table[i],
+                    table[j],
+                    1 + pair_played_with.get_value(table[i], table[j]).unwrap_or(0),
+                );
+            }
+        }
+    }
+
+    pair_played_with
+}
+
+/// Repeated meetings of pairs add +1 to factor, meetings in two sequential sessions add +10.
+/// Less factor value is better!
+fn calculate_pairs_factor(
+    seating: &PlayersMap,
+    pair_played_with: &Matrix<u32>,
+    last_session_played_with: &Matrix<u32>,
+) -> i32 {
+    let mut factor = 0;
+
+    for table in seating.chunks(2) {
+        if table.len() < 2 {
+            continue;
+        }
+        factor += pair_played_with
+            .get_value(table[0].0, table[1].0)
+            .unwrap_or(0) as i32;
+        factor += 10
+            * last_session_played_with
+                .get_value(table[0].0, table[1].0)
+                .unwrap_or(0) as i32;
+    }
+
+    factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_pairs_seating() {
+        let players: PlayersMap = (1..=16).map(|id| (id, 1500)).collect();
+        let pairs: Vec<(u32, u32)> = (1..=8).map(|id| (id, id + 8)).collect();
+        let mut previous_seatings: Vec<Vec<u32>> = Vec::new();
+
+        for session in 0..3 {
+            let seating =
+                make_pairs_seating(&players, &pairs, &previous_seatings, 12345 + session).unwrap();
+            assert_eq!(seating.len(), 16);
+
+            for table in seating.chunks(4) {
+                // Partners sit opposite
+                assert_eq!(table[0].0 + 8, table[2].0);
+                assert_eq!(table[1].0 + 8, table[3].0);
+                previous_seatings.push(table.iter().map(|(id, _)| *id).collect());
+            }
+        }
+
+        // No pair met another pair twice
+        for i in 0..previous_seatings.len() {
+            for j in i + 1..previous_seatings.len() {
+                let mut first = previous_seatings[i][..2].to_vec();
+                let mut second = previous_seatings[j][..2].to_vec();
+                first.sort();
+                second.sort();
+                assert_ne!(first, second);
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_pairs_seating_errors() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+
+        let pairs = vec![(1, 2), (3, 4), (5, 6)];
+        assert_eq!(
+            make_pairs_seating(&players, &pairs, &[], 12345),
+            Err(PairsError::OddPairsCount { count: 3 })
+        );
+
+        let pairs = vec![(1, 2), (3, 9)];
+        let result = make_pairs_seating(&players, &pairs, &[], 12345);
+        assert_eq!(result, Err(PairsError::UnknownPlayer { id: 9 }));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "player 9 of pairs is unknown"
+        );
+
+        assert_eq!(
+            make_pairs_seating(&players, &[], &[], 12345),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_make_pairs_seating_self_pair() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let result = make_pairs_seating(&players, &[(1, 2), (3, 3)], &[], 12345);
+        assert_eq!(result, Err(PairsError::SelfPair { id: 3 }));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "player 3 is paired with themselves"
+        );
+    }
+
+    #[test]
+    fn test_make_pairs_seating_duplicate_player() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let result = make_pairs_seating(&players, &[(1, 2), (3, 4), (5, 6), (2, 7)], &[], 12345);
+        assert_eq!(result, Err(PairsError::DuplicatePlayer { id: 2 }));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "player 2 is listed in more than one pair"
+        );
+    }
+
+    #[test]
+    fn test_calculate_pairs_factor() {
+        let pair_seatings = vec![vec![1, 2], vec![3, 4], vec![1, 3], vec![2, 4]];
+        let pair_played_with = make_pair_played_with_matrix(4, &pair_seatings);
+        let last_session_played_with = make_pair_played_with_matrix(4, &pair_seatings[2..]);
+
+        let seating = vec![(1, 3000), (2, 3000), (3, 3000), (4, 3000)];
+        assert_eq!(
+            calculate_pairs_factor(&seating, &pair_played_with, &last_session_played_with),
+            2
+        );
+        let seating = vec![(1, 3000), (3, 3000), (2, 3000), (4, 3000)];
+        assert_eq!(
+            calculate_pairs_factor(&seating, &pair_played_with, &last_session_played_with),
+            22
+        );
+        let seating = vec![(1, 3000), (4, 3000), (2, 3000), (3, 3000)];
+        assert_eq!(
+            calculate_pairs_factor(&seating, &pair_played_with, &last_session_played_with),
+            0
+        );
+    }
+}
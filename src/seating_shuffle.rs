@@ -1,52 +1,45 @@
-use crate::interfaces::PlayersMap;
+use crate::interfaces::{PlayersMap, WindPolicy};
 use crate::shuffle::shuffle;
+use crate::winds::apply_wind_policy;
 use lcg_rand::rand::LCG;
 
-const POSSIBLE_PLACEMENTS: [(u8, u8, u8, u8); 24] = [
-    (0u8, 1u8, 2u8, 3u8),
-    (1u8, 0u8, 2u8, 3u8),
-    (2u8, 0u8, 1u8, 3u8),
-    (3u8, 0u8, 1u8, 2u8),
-    (0u8, 1u8, 3u8, 2u8),
-    (1u8, 0u8, 3u8, 2u8),
-    (2u8, 0u8, 3u8, 1u8),
-    (3u8, 0u8, 2u8, 1u8),
-    (0u8, 2u8, 1u8, 3u8),
-    (1u8, 2u8, 0u8, 3u8),
-    (2u8, 1u8, 0u8, 3u8),
-    (3u8, 1u8, 0u8, 2u8),
-    (0u8, 2u8, 3u8, 1u8),
-    (1u8, 2u8, 3u8, 0u8),
-    (2u8, 1u8, 3u8, 0u8),
-    (3u8, 1u8, 2u8, 0u8),
-    (0u8, 3u8, 1u8, 2u8),
-    (1u8, 3u8, 0u8, 2u8),
-    (2u8, 3u8, 0u8, 1u8),
-    (3u8, 2u8, 0u8, 1u8),
-    (0u8, 3u8, 2u8, 1u8),
-    (1u8, 3u8, 2u8, 0u8),
-    (2u8, 3u8, 1u8, 0u8),
-    (3u8, 2u8, 1u8, 0u8),
-];
-
 /// Shuffled seating with random optimization
 /// Note: placement uses previous seatings to try to minimize crossings, so this is not a fair random in general.
 pub fn make_shuffled_seating(
     players_map: &PlayersMap,
-    previous_seatings: &Vec<Vec<u32>>,
+    previous_seatings: &[Vec<u32>],
     groups_count: u32,
+    wind_policy: WindPolicy,
     rand_factor: u64,
 ) -> PlayersMap {
-    const MAX_ITERATIONS: usize = 1000;
-    let mut best_seating = Vec::new();
-    let mut factor = 100500; // lower is better, so init with very big number
-
     if players_map.is_empty() {
         return Vec::new();
     }
 
+    let best_seating = find_best_shuffle(players_map, groups_count, rand_factor, |seating| {
+        calculate_intersection_factor(seating, previous_seatings)
+    });
+
+    apply_wind_policy(&best_seating, previous_seatings, wind_policy, rand_factor)
+}
+
+/// Random optimization: shuffle players inside each group many times and take
+/// the seating with lowest factor.
+pub(crate) fn find_best_shuffle<F>(
+    players_map: &PlayersMap,
+    groups_count: u32,
+    rand_factor: u64,
+    calc_factor: F,
+) -> PlayersMap
+where
+    F: Fn(&PlayersMap) -> i32,
+{
+    const MAX_ITERATIONS: usize = 1000;
+    let mut best_seating = Vec::new();
+    let mut factor = 100500; // lower is better, so init with very big number
+
     // Split into groups
-    let group_size = (players_map.len() as u32 + groups_count - 1) / groups_count;
+    let group_size = (players_map.len() as u32).div_ceil(groups_count);
     let mut groups: Vec<PlayersMap> = Vec::new();
 
     for chunk in players_map.chunks(group_size as usize) {
@@ -64,62 +57,20 @@ pub fn make_shuffled_seating(
         // Flatten groups
         let flattened_groups: PlayersMap = groups.iter().flatten().copied().collect();
 
-        let new_factor = calculate_intersection_factor(&flattened_groups, previous_seatings);
+        let new_factor = calc_factor(&flattened_groups);
         if new_factor < factor {
             factor = new_factor;
             best_seating = flattened_groups;
         }
     }
 
-    update_places_at_each_table(&best_seating, previous_seatings)
-}
-
-/// Make sure players will initially sit to winds that they did not seat before
-/// (or sat less times)
-fn update_places_at_each_table(
-    seating: &PlayersMap,
-    previous_seatings: &Vec<Vec<u32>>,
-) -> PlayersMap {
-    let mut tables = Vec::new();
-    for chunk in seating.chunks(4) {
-        tables.push(chunk.to_vec());
-    }
-
-    let mut result_seating = Vec::new();
-    for table in tables {
-        let mut best_result = 10005000;
-        let mut best_placement = Vec::new();
-
-        for placement in &POSSIBLE_PLACEMENTS {
-            let new_result = calc_sub_sums(
-                table[placement.0 as usize].0,
-                table[placement.1 as usize].0,
-                table[placement.2 as usize].0,
-                table[placement.3 as usize].0,
-                previous_seatings,
-            );
-
-            if new_result < best_result {
-                best_result = new_result;
-                best_placement = vec![
-                    table[placement.0 as usize],
-                    table[placement.1 as usize],
-                    table[placement.2 as usize],
-                    table[placement.3 as usize],
-                ];
-            }
-        }
-
-        result_seating.extend(best_placement);
-    }
-
-    result_seating
+    best_seating
 }
 
 /// Calculate generalized value of seating applicability.
 /// Sequential games of same players add +10 to factor, while simple crossings add only +1.
 /// Less factor value is better!
-fn calculate_intersection_factor(seating: &PlayersMap, previous_seatings: &Vec<Vec<u32>>) -> i32 {
+fn calculate_intersection_factor(seating: &PlayersMap, previous_seatings: &[Vec<u32>]) -> i32 {
     let mut factor = 0;
     let mut crossings: Vec<Vec<Vec<u32>>> = Vec::new();
 
@@ -176,63 +127,10 @@ fn calculate_intersection_factor(seating: &PlayersMap, previous_seatings: &Vec<V
     factor / 2 // div by 2 because of symmetrical matrix counting
 }
 
-/// Calculate index of distribution equality for seating at particular
-/// winds. Ideally, we want that seating, which produces smallest index.
-fn calc_sub_sums(
-    player1: u32,
-    player2: u32,
-    player3: u32,
-    player4: u32,
-    prev_data: &Vec<Vec<u32>>,
-) -> u32 {
-    let mut total_sum = 0;
-
-    for (idx, &player) in [player1, player2, player3, player4].iter().enumerate() {
-        let mut buckets = [0u32, 0u32, 0u32, 0u32];
-        buckets[idx] += 1;
-
-        for table in prev_data {
-            if let Some(idx_at_table) = table.iter().position(|&p| p == player) {
-                buckets[idx_at_table] += 1;
-            }
-        }
-
-        total_sum += buckets[0].abs_diff(buckets[1])
-            + buckets[0].abs_diff(buckets[2])
-            + buckets[0].abs_diff(buckets[3])
-            + buckets[1].abs_diff(buckets[2])
-            + buckets[1].abs_diff(buckets[3])
-            + buckets[2].abs_diff(buckets[3]);
-    }
-
-    total_sum
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_calc_sub_sums() {
-        let prev_seating: Vec<Vec<u32>> =
-            vec![vec![1, 2, 3, 4], vec![4, 1, 2, 3], vec![3, 4, 1, 2]];
-
-        assert_eq!(calc_sub_sums(1, 2, 3, 4, &prev_seating), 24);
-    }
-
-    #[test]
-    fn test_update_places_at_each_table() {
-        let players = vec![(1, 1500), (2, 1500), (3, 1500), (4, 1500)];
-
-        let previous_seating: Vec<Vec<u32>> =
-            vec![vec![1, 2, 3, 4], vec![4, 1, 2, 3], vec![3, 4, 1, 2]];
-
-        assert_eq!(
-            update_places_at_each_table(&players, &previous_seating),
-            vec![(2, 1500), (3, 1500), (4, 1500), (1, 1500)]
-        )
-    }
-
     #[test]
     fn test_calculate_intersection_factor() {
         let players = vec![
@@ -288,7 +186,8 @@ mod tests {
             (12, 1500),
         ];
 
-        let seating = make_shuffled_seating(&players, &vec![], 1, 3464752);
+        let seating =
+            make_shuffled_seating(&players, &[], 1, WindPolicy::BalancedByHistory, 3464752);
         assert_eq!(seating.len(), 12);
         assert_eq!(
             seating,
@@ -337,7 +236,13 @@ mod tests {
             vec![13, 14, 15, 16],
         ];
 
-        let seating = make_shuffled_seating(&players, &previous_seating, 1, 123456);
+        let seating = make_shuffled_seating(
+            &players,
+            &previous_seating,
+            1,
+            WindPolicy::BalancedByHistory,
+            123456,
+        );
         assert_eq!(seating.len(), 16);
         assert_eq!(
             seating,
@@ -394,7 +299,13 @@ mod tests {
             vec![4, 8, 12, 16],
         ];
 
-        let seating = make_shuffled_seating(&players, &previous_seating, 1, 9486370);
+        let seating = make_shuffled_seating(
+            &players,
+            &previous_seating,
+            1,
+            WindPolicy::BalancedByHistory,
+            9486370,
+        );
         assert_eq!(seating.len(), 16);
         assert_eq!(
             seating,
@@ -447,7 +358,13 @@ mod tests {
             vec![13, 14, 15, 16],
         ];
 
-        let seating = make_shuffled_seating(&players, &previous_seating, 2, 3464752);
+        let seating = make_shuffled_seating(
+            &players,
+            &previous_seating,
+            2,
+            WindPolicy::BalancedByHistory,
+            3464752,
+        );
         assert_eq!(seating.len(), 16);
         assert_eq!(
             seating,
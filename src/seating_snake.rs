@@ -0,0 +1,268 @@
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::matrix::Matrix;
+use crate::seating_swiss::make_played_with_matrix;
+use crate::winds::apply_wind_policy;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum SnakeError {
+    PlayersCount { count: usize }, // Players count is not a multiple of 4
+}
+
+impl fmt::Display for SnakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnakeError::PlayersCount { count } => {
+                write!(f, "players count should be a multiple of 4, got {count}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnakeError {}
+
+/// Snake (serpentine) seating
+/// Top players are placed to tables 1..N in order, next N players in reverse order, and so on,
+/// so every table gets similar total rating.
+/// If previous seatings are not empty, players of the same snake row are swapped between
+/// tables to avoid repeated pairs.
+/// Returns error if players count is not a multiple of 4.
+pub fn make_snake_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, SnakeError> {
+    let mut tables = make_snake_tables(players_map)?;
+
+    if !previous_seatings.is_empty() {
+        let played_with = make_played_with_matrix(players_map, previous_seatings);
+        reduce_repeats_by_row_swaps(&mut tables, &played_with);
+    }
+
+    let flattened_groups: PlayersMap = tables.into_iter().flatten().collect();
+    Ok(apply_wind_policy(
+        &flattened_groups,
+        previous_seatings,
+        wind_policy,
+        rand_factor,
+    ))
+}
+
+/// Split players sorted by rating into tables in serpentine order.
+/// Player at index N of each table comes from N-th snake row.
+pub(crate) fn make_snake_tables(players_map: &PlayersMap) -> Result<Vec<PlayersMap>, SnakeError> {
+    if !players_map.len().is_multiple_of(4) {
+        return Err(SnakeError::PlayersCount {
+            count: players_map.len(),
+        });
+    }
+    let mut sorted_players = players_map.clone();
+    sorted_players.sort_by_key(|item| std::cmp::Reverse(item.1));
+    Ok(split_to_snake_tables(&sorted_players))
+}
+
+/// Split players into tables in serpentine order, keeping order of the input list.
+/// Players count should be a multiple of 4.
+pub(crate) fn split_to_snake_tables(sorted_players: &[(u32, i32)]) -> Vec<PlayersMap> {
+    let tables_count = sorted_players.len() / 4;
+    let mut tables: Vec<PlayersMap> = vec![Vec::new(); tables_count];
+    if tables_count == 0 {
+        return tables;
+    }
+
+    for (row_index, row) in sorted_players.chunks(tables_count).enumerate() {
+        for (index, player) in row.iter().enumerate() {
+            let table_index = if row_index % 2 == 0 {
+                index
+            } else {
+                tables_count - 1 - index
+            };
+            tables[table_index].push(*player);
+        }
+    }
+
+    tables
+}
+
+/// Swap players of the same snake row between tables while it decreases count of repeated pairs.
+fn reduce_repeats_by_row_swaps(tables: &mut [PlayersMap], played_with: &Matrix<u32>) {
+    const MAX_PASSES: usize = 10;
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for row in 0..4 {
+            for table1 in 0..tables.len() {
+                for table2 in table1 + 1..tables.len() {
+                    let before = count_table_repeats(&tables[table1], played_with)
+                        + count_table_repeats(&tables[table2], played_with);
+
+                    let player1 = tables[table1][row];
+                    tables[table1][row] = tables[table2][row];
+                    tables[table2][row] = player1;
+
+                    let after = count_table_repeats(&tables[table1], played_with)
+                        + count_table_repeats(&tables[table2], played_with);
+
+                    if after < before {
+                        improved = true;
+                    } else {
+                        // Swap back, this exchange does not help
+                        tables[table2][row] = tables[table1][row];
+                        tables[table1][row] = player1;
+                    }
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+/// Count how many times players of the table have already played with each other
+pub(crate) fn count_table_repeats(table: &[(u32, i32)], played_with: &Matrix<u32>) -> u32 {
+    let mut repeats = 0;
+    for i in 0..table.len() {
+        for j in i + 1..table.len() {
+            repeats += played_with.get_value(table[i].0, table[j].0).unwrap_or(0);
+        }
+    }
+    repeats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersections::make_intersections_table;
+
+    fn sorted_tables(seating: &PlayersMap) -> Vec<Vec<u32>> {
+        seating
+            .chunks(4)
+            .map(|table| {
+                let mut ids: Vec<u32> = table.iter().map(|(id, _)| *id).collect();
+                ids.sort();
+                ids
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_make_snake_tables() {
+        let players = vec![
+            (1, 1508),
+            (2, 1507),
+            (3, 1506),
+            (4, 1505),
+            (5, 1504),
+            (6, 1503),
+            (7, 1502),
+            (8, 1501),
+            (9, 1500),
+            (10, 1499),
+            (11, 1498),
+            (12, 1497),
+        ];
+
+        let tables = make_snake_tables(&players).unwrap();
+        assert_eq!(
+            tables,
+            vec![
+                vec![(1, 1508), (6, 1503), (7, 1502), (12, 1497)],
+                vec![(2, 1507), (5, 1504), (8, 1501), (11, 1498)],
+                vec![(3, 1506), (4, 1505), (9, 1500), (10, 1499)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_snake_seating() {
+        let players = vec![
+            (16, 1494),
+            (1, 1509),
+            (2, 1508),
+            (3, 1507),
+            (4, 1506),
+            (5, 1505),
+            (6, 1504),
+            (7, 1503),
+            (8, 1502),
+            (9, 1501),
+            (10, 1500),
+            (11, 1499),
+            (12, 1498),
+            (13, 1497),
+            (14, 1496),
+            (15, 1495),
+        ];
+
+        let seating = make_snake_seating(&players, &[], WindPolicy::Random, 12345).unwrap();
+        assert_eq!(
+            sorted_tables(&seating),
+            vec![
+                vec![1, 8, 9, 16],
+                vec![2, 7, 10, 15],
+                vec![3, 6, 11, 14],
+                vec![4, 5, 12, 13],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_snake_seating_with_history() {
+        let players = vec![
+            (1, 1509),
+            (2, 1508),
+            (3, 1507),
+            (4, 1506),
+            (5, 1505),
+            (6, 1504),
+            (7, 1503),
+            (8, 1502),
+            (9, 1501),
+            (10, 1500),
+            (11, 1499),
+            (12, 1498),
+            (13, 1497),
+            (14, 1496),
+            (15, 1495),
+            (16, 1494),
+        ];
+
+        // Exactly the tables plain snake seating would produce
+        let previous_seatings = vec![
+            vec![1, 8, 9, 16],
+            vec![2, 7, 10, 15],
+            vec![3, 6, 11, 14],
+            vec![4, 5, 12, 13],
+        ];
+
+        let seating =
+            make_snake_seating(&players, &previous_seatings, WindPolicy::Random, 12345).unwrap();
+        assert_eq!(seating.len(), 16);
+
+        let intersections = make_intersections_table(&seating, &previous_seatings);
+        intersections.iter().for_each(|item| assert!(item.2.le(&1)));
+
+        // Each table still has one player from every snake row
+        sorted_tables(&seating).iter().for_each(|table| {
+            assert!(table[0] <= 4);
+            assert!(table[1] >= 5 && table[1] <= 8);
+            assert!(table[2] >= 9 && table[2] <= 12);
+            assert!(table[3] >= 13);
+        });
+    }
+
+    #[test]
+    fn test_make_snake_seating_players_count() {
+        let players: PlayersMap = (1..=10).map(|id| (id, 1500 - id as i32)).collect();
+        let error = make_snake_seating(&players, &[], WindPolicy::Fixed, 12345).unwrap_err();
+        assert_eq!(error, SnakeError::PlayersCount { count: 10 });
+        assert_eq!(
+            error.to_string(),
+            "players count should be a multiple of 4, got 10"
+        );
+    }
+}
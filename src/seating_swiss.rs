@@ -1,44 +1,190 @@
-use crate::interfaces::PlayersMap;
+use crate::interfaces::{PlayersMap, Standing, SwissSeating, TieBreak, WindPolicy};
 use crate::matrix::Matrix;
 use crate::minimap::Minimap;
-use crate::shuffle::update_places_to_random;
+use crate::quality::make_quality_report;
+use crate::standings::make_rank_keys;
+use crate::winds::apply_wind_policy;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Swiss seating entry point
 /// Wrapper for formats conformity
 pub fn make_swiss_seating(
     players_map: &PlayersMap,
-    previous_seatings: &Vec<Vec<u32>>,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
     rand_factor: u64,
 ) -> PlayersMap {
+    make_swiss_seating_with_repeats(players_map, previous_seatings, wind_policy, rand_factor)
+        .seating
+}
+
+/// Swiss seating which also reports previous meetings of players seated at the same tables
+pub fn make_swiss_seating_with_repeats(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> SwissSeating {
+    make_cancellable_swiss_seating(
+        players_map,
+        previous_seatings,
+        wind_policy,
+        rand_factor,
+        &AtomicBool::new(false),
+    )
+    .expect("seating is not cancelled")
+}
+
+/// Swiss seating which stops searching once $cancel is set, e.g. from another thread after
+/// time budget is over. Returns None if seating was cancelled.
+pub fn make_cancellable_swiss_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+    cancel: &AtomicBool,
+) -> Option<SwissSeating> {
     let ids: Vec<u32> = players_map.iter().map(|item| item.0).collect();
     let mut played_with = make_played_with_matrix(players_map, previous_seatings);
     let mut player_to_rating: Minimap<i32> = Minimap::new(ids.len());
     players_map.iter().for_each(|item| {
         player_to_rating.set_value(item.0, item.1);
     });
-    let player_table = swiss_seating_original(&players_map, &ids, &mut played_with);
+    let player_table = swiss_seating_cancellable(players_map, &ids, &mut played_with, cancel)?;
 
     let mut result_table: Vec<(u32, i32)> = Vec::new();
     for i in ids {
         result_table.push((i, player_table.get_value(i).unwrap()));
     }
-    result_table.sort_by(|a, b| a.1.cmp(&b.1));
+    result_table.sort_by_key(|a| a.1);
     result_table = result_table
         .iter()
         .map(|item| (item.0, player_to_rating.get_value(item.0).unwrap()))
         .collect();
 
-    update_places_to_random(&result_table, rand_factor)
+    let quality = make_quality_report(&result_table, previous_seatings);
+
+    Some(SwissSeating {
+        seating: apply_wind_policy(&result_table, previous_seatings, wind_policy, rand_factor),
+        repeats: quality.repeats,
+        max_repeats: quality.max_repeats,
+    })
+}
+
+/// Swiss seating by tournament standings
+/// Players are ordered by points and then by $tie_breaks (see compare_standings), so players
+/// with equal points are placed deterministically instead of by input order.
+/// Returns (id, points) pairs.
+pub fn make_swiss_seating_by_standings(
+    standings: &[Standing],
+    tie_breaks: &[TieBreak],
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> PlayersMap {
+    let rank_keys = make_rank_keys(standings, tie_breaks);
+    let mut player_to_points: Minimap<i32> = Minimap::new(standings.len());
+    standings.iter().for_each(|standing| {
+        player_to_points.set_value(standing.id, standing.points);
+    });
+
+    make_swiss_seating(&rank_keys, previous_seatings, wind_policy, rand_factor)
+        .iter()
+        .map(|item| (item.0, player_to_points.get_value(item.0).unwrap()))
+        .collect()
+}
+
+/// McMahon-style swiss seating entry point
+/// Players are grouped into bands of $band_width rating points and seated with swiss
+/// algorithm inside their band. If band size is not divisible by 4, lowest rated players
+/// of the band float down to the next band.
+/// $band_width should be positive: zero or negative width is treated as 1 rating point
+/// (strict::make_mcmahon_seating rejects it).
+pub fn make_mcmahon_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    band_width: i32,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> PlayersMap {
+    let mut played_with = make_played_with_matrix(players_map, previous_seatings);
+    let mut result_table: Vec<(u32, i32)> = Vec::new();
+
+    for band in make_rating_bands(players_map, band_width) {
+        let ids: Vec<u32> = band.iter().map(|item| item.0).collect();
+        let mut player_to_rating: Minimap<i32> = Minimap::new(ids.len());
+        player_to_rating.fill_with(&band);
+
+        // Crossing limits are searched for each band separately, while played_with matrix is shared
+        let player_table = swiss_seating_original(&band, &ids, &mut played_with);
+
+        let mut band_table: Vec<(u32, i32)> = Vec::new();
+        for i in ids {
+            band_table.push((i, player_table.get_value(i).unwrap()));
+        }
+        band_table.sort_by_key(|a| a.1);
+        result_table.extend(
+            band_table
+                .iter()
+                .map(|item| (item.0, player_to_rating.get_value(item.0).unwrap())),
+        );
+    }
+
+    apply_wind_policy(&result_table, previous_seatings, wind_policy, rand_factor)
+}
+
+/// Split players into rating bands, from highest to lowest.
+/// Each band size is divisible by 4: extra players with lowest rating are floated to the next band.
+/// Non-positive $band_width is clamped to 1.
+fn make_rating_bands(players_map: &PlayersMap, band_width: i32) -> Vec<PlayersMap> {
+    let mut sorted_players = players_map.clone();
+    sorted_players.sort_by_key(|item| std::cmp::Reverse(item.1));
+
+    let mut bands: Vec<PlayersMap> = Vec::new();
+    let mut current_band: PlayersMap = Vec::new();
+    let mut current_band_index = None;
+
+    for player in sorted_players {
+        let band_index = player.1.div_euclid(band_width.max(1));
+        if current_band_index.is_some_and(|index| index != band_index) {
+            // Floaters are the lowest rated players of the band, they join the next one
+            let floaters = current_band.split_off(current_band.len() - current_band.len() % 4);
+            if !current_band.is_empty() {
+                bands.push(current_band);
+            }
+            current_band = floaters;
+        }
+        current_band_index = Some(band_index);
+        current_band.push(player);
+    }
+
+    if !current_band.is_empty() {
+        bands.push(current_band);
+    }
+
+    bands
 }
 
 /// Swiss seating generator
 /// Algorithm was taken from mahjongsoft.ru website.
 /// Returns hash map (player_id, table_index) tuples
-fn swiss_seating_original(
+pub(crate) fn swiss_seating_original(
     players_ratings: &PlayersMap,
-    ids: &Vec<u32>,
+    ids: &[u32],
     played_with: &mut Matrix<u32>,
 ) -> Minimap<i32> {
+    swiss_seating_cancellable(players_ratings, ids, played_with, &AtomicBool::new(false))
+        .expect("seating is not cancelled")
+}
+
+/// Swiss seating generator which gives up once $cancel is set.
+/// Returns None if it was cancelled before seating was found.
+fn swiss_seating_cancellable(
+    players_ratings: &PlayersMap,
+    ids: &[u32],
+    played_with: &mut Matrix<u32>,
+    cancel: &AtomicBool,
+) -> Option<Minimap<i32>> {
     let mut player_table = Minimap::new(ids.len());
     let mut is_playing = Minimap::new(ids.len());
     let mut max_crossings = 0;
@@ -60,17 +206,22 @@ fn swiss_seating_original(
         &mut player_table,
         played_with,
         &mut iteration,
+        cancel,
     ) {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
         max_crossings += 1;
     }
 
-    player_table
+    Some(player_table)
 }
 
 /// Recursive swiss seating algorithm.
 /// Taken from mahjongsoft.ru
+#[allow(clippy::too_many_arguments)]
 fn swiss_seating_internal(
-    ids: &Vec<u32>,
+    ids: &[u32],
     max_crossings: u32,
     players_ratings: &Minimap<i32>, // player_id -> rating
     is_playing: &mut Minimap<bool>, // player_id -> is playing
@@ -78,7 +229,13 @@ fn swiss_seating_internal(
     player_table: &mut Minimap<i32>,
     played_with: &mut Matrix<u32>,
     iteration: &mut u32,
+    cancel: &AtomicBool,
 ) -> bool {
+    // Cancelled search unwinds as if no seating was found
+    if cancel.load(Ordering::Relaxed) {
+        return false;
+    }
+
     *iteration += 1;
     if *iteration > 15000 {
         *max_crossings_precision_factor += 1;
@@ -94,12 +251,12 @@ fn swiss_seating_internal(
 
     // if table is already filled, take next table and place there a player with highest rating
 
-    if players_on_max_table.len() == 0 || players_on_max_table.len() == 4 {
+    if players_on_max_table.is_empty() || players_on_max_table.len() == 4 {
         if players_on_max_table.len() == 4 {
             max_table += 1;
         }
 
-        let max_rating_player = find_player_with_highest_rating(ids, is_playing, &players_ratings);
+        let max_rating_player = find_player_with_highest_rating(ids, is_playing, players_ratings);
 
         // check 'playing' flag and place the player to the table, then call the procedure recursively
 
@@ -115,6 +272,7 @@ fn swiss_seating_internal(
             player_table,
             played_with,
             iteration,
+            cancel,
         ) {
             true
         } else {
@@ -131,10 +289,8 @@ fn swiss_seating_internal(
             for i in ids {
                 if !is_playing.get_value(*i).unwrap() {
                     let mut num_crossings = 0;
-                    for j in 0..players_on_max_table.len() {
-                        num_crossings += played_with
-                            .get_value(*i, players_on_max_table[j])
-                            .unwrap_or(0);
+                    for j in &players_on_max_table {
+                        num_crossings += played_with.get_value(*i, *j).unwrap_or(0);
                     }
                     if num_crossings <= cur_crossings {
                         next_players.push(*i);
@@ -142,7 +298,7 @@ fn swiss_seating_internal(
                 }
             }
 
-            if next_players.len() > 0 {
+            if !next_players.is_empty() {
                 break;
             } else if cur_crossings == max_crossings + *max_crossings_precision_factor {
                 return false;
@@ -154,7 +310,7 @@ fn swiss_seating_internal(
         sort_by_rating(&mut next_players, players_ratings);
 
         // substitute candidates for seating, then make a check
-        for i in 0..next_players.len() {
+        for next_player in next_players {
             // check 'playing' flag and place the player to the table, then call the procedure recursively
             set_table_for_player(
                 is_playing,
@@ -162,7 +318,7 @@ fn swiss_seating_internal(
                 played_with,
                 true,
                 max_table,
-                next_players[i],
+                next_player,
                 &players_on_max_table,
             );
 
@@ -176,6 +332,7 @@ fn swiss_seating_internal(
                 player_table,
                 played_with,
                 iteration,
+                cancel,
             ) {
                 return true;
             } else {
@@ -185,7 +342,7 @@ fn swiss_seating_internal(
                     played_with,
                     false,
                     -1,
-                    next_players[i],
+                    next_player,
                     &players_on_max_table,
                 );
             }
@@ -195,9 +352,9 @@ fn swiss_seating_internal(
     }
 }
 
-fn make_played_with_matrix(
+pub(crate) fn make_played_with_matrix(
     players_map: &PlayersMap,
-    previous_seatings: &Vec<Vec<u32>>,
+    previous_seatings: &[Vec<u32>],
 ) -> Matrix<u32> {
     let ids: Vec<u32> = players_map.iter().map(|item| item.0).collect();
     let mut played_with = Matrix::new(ids.len());
@@ -222,7 +379,7 @@ fn make_played_with_matrix(
 }
 
 /// Find table with highest index and players count already at that table
-fn find_highest_table_and_players(ids: &Vec<u32>, player_table: &Minimap<i32>) -> (i32, Vec<u32>) {
+fn find_highest_table_and_players(ids: &[u32], player_table: &Minimap<i32>) -> (i32, Vec<u32>) {
     let mut max_table = 0;
     let mut players_on_max_table = Vec::new();
     for i in ids {
@@ -235,12 +392,12 @@ fn find_highest_table_and_players(ids: &Vec<u32>, player_table: &Minimap<i32>) -
         }
     }
 
-    players_on_max_table.sort_by(|a, b| a.cmp(b));
+    players_on_max_table.sort();
     (max_table, players_on_max_table)
 }
 
 /// Sort first array by rating (mutating)
-fn sort_by_rating(next_players: &mut Vec<u32>, players_ratings: &Minimap<i32>) {
+fn sort_by_rating(next_players: &mut [u32], players_ratings: &Minimap<i32>) {
     for i in 0..next_players.len() {
         if i + 1 == next_players.len() {
             continue;
@@ -256,7 +413,7 @@ fn sort_by_rating(next_players: &mut Vec<u32>, players_ratings: &Minimap<i32>) {
 }
 
 fn find_player_with_highest_rating(
-    ids: &Vec<u32>,
+    ids: &[u32],
     is_playing: &Minimap<bool>,
     players_ratings: &Minimap<i32>,
 ) -> u32 {
@@ -283,37 +440,30 @@ fn set_table_for_player(
     set_is_playing: bool,
     set_table_number: i32,
     player: u32,
-    players_on_max_table: &Vec<u32>,
+    players_on_max_table: &[u32],
 ) {
     is_playing.set_value(player, set_is_playing);
     player_table.set_value(player, set_table_number);
-    for j in 0..players_on_max_table.len() {
+    for other in players_on_max_table {
         if set_is_playing {
             played_with.set_value(
                 player,
-                players_on_max_table[j],
-                played_with
-                    .get_value(player, players_on_max_table[j])
-                    .unwrap_or(0)
-                    + 1,
+                *other,
+                played_with.get_value(player, *other).unwrap_or(0) + 1,
             )
         } else {
-            let cur = played_with
-                .get_value(player, players_on_max_table[j])
-                .unwrap();
-            played_with.set_value(
-                player,
-                players_on_max_table[j],
-                if cur == 0 { 0 } else { cur - 1 },
-            )
+            let cur = played_with.get_value(player, *other).unwrap();
+            played_with.set_value(player, *other, if cur == 0 { 0 } else { cur - 1 })
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use crate::intersections::make_intersections_table;
+    use crate::quality::make_quality_report;
     // use crate::intersections::make_intersections_table;
 
     #[test]
@@ -581,10 +731,241 @@ mod tests {
             vec![12, 3, 9, 15],
         ];
 
-        let seating = make_swiss_seating(&players, &previous_seatings, 12345);
+        let seating = make_swiss_seating(&players, &previous_seatings, WindPolicy::Random, 12345);
         let intersections = make_intersections_table(&seating, &previous_seatings);
 
         // Swiss seating should produce seating of 32 players in 8 games with no more than 2 intersections of each pair
         intersections.iter().for_each(|item| assert!(item.2.le(&2)));
     }
+
+    #[test]
+    fn test_swiss_seating_with_repeats() {
+        let players: PlayersMap = (1..=4).map(|id| (id, 1500)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![4, 3, 2, 1]];
+        let result =
+            make_swiss_seating_with_repeats(&players, &previous_seatings, WindPolicy::Fixed, 12345);
+        assert_eq!(result.seating.len(), 4);
+        assert_eq!(result.repeats, 12);
+        assert_eq!(result.max_repeats, 2);
+
+        // Repeats are the same as in quality report of produced seating
+        let players: PlayersMap = (1..=16).map(|id| (id, 2000 - 10 * id as i32)).collect();
+        let previous_seatings = vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+            vec![13, 14, 15, 16],
+            vec![1, 5, 9, 13],
+            vec![2, 6, 10, 14],
+        ];
+        let result = make_swiss_seating_with_repeats(
+            &players,
+            &previous_seatings,
+            WindPolicy::Random,
+            12345,
+        );
+        let report = make_quality_report(&result.seating, &previous_seatings);
+        assert_eq!(result.repeats, report.repeats);
+        assert_eq!(result.max_repeats, report.max_repeats);
+        assert_eq!(
+            result.seating,
+            make_swiss_seating(&players, &previous_seatings, WindPolicy::Random, 12345)
+        );
+    }
+
+    #[test]
+    fn test_cancellable_swiss_seating() {
+        let players: PlayersMap = (1..=16).map(|id| (id, 2000 - 10 * id as i32)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+
+        let result = make_cancellable_swiss_seating(
+            &players,
+            &previous_seatings,
+            WindPolicy::Fixed,
+            0,
+            &AtomicBool::new(false),
+        );
+        assert_eq!(
+            result.unwrap().seating,
+            make_swiss_seating(&players, &previous_seatings, WindPolicy::Fixed, 0)
+        );
+
+        let result = make_cancellable_swiss_seating(
+            &players,
+            &previous_seatings,
+            WindPolicy::Fixed,
+            0,
+            &AtomicBool::new(true),
+        );
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_swiss_seating_by_standings() {
+        // All players have equal points, so tables are made by uma
+        let standings: Vec<Standing> = (1..=8)
+            .map(|id| Standing {
+                id,
+                points: 0,
+                uma: if id % 2 == 0 {
+                    10 * id as i32
+                } else {
+                    -10 * id as i32
+                },
+                placements: [0, 0, 0, 0],
+            })
+            .collect();
+
+        let seating = make_swiss_seating_by_standings(
+            &standings,
+            &[TieBreak::Uma],
+            &[],
+            WindPolicy::ByRating,
+            12345,
+        );
+        assert_eq!(
+            seating,
+            vec![
+                (8, 0),
+                (6, 0),
+                (4, 0),
+                (2, 0),
+                (1, 0),
+                (3, 0),
+                (5, 0),
+                (7, 0)
+            ]
+        );
+
+        // Without tie-breaks players are ordered by id
+        let seating =
+            make_swiss_seating_by_standings(&standings, &[], &[], WindPolicy::ByRating, 12345);
+        let ids: Vec<u32> = seating.iter().map(|item| item.0).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_make_rating_bands() {
+        let players = vec![
+            (1, 3400),
+            (2, 3300),
+            (3, 3200),
+            (4, 3100),
+            (5, 3000),
+            (6, 2800),
+            (7, 2700),
+            (8, 2600),
+            (9, 1500),
+            (10, 1400),
+            (11, 1300),
+            (12, 1200),
+        ];
+
+        let bands = make_rating_bands(&players, 1000);
+        assert_eq!(
+            bands,
+            vec![
+                vec![(1, 3400), (2, 3300), (3, 3200), (4, 3100)],
+                vec![(5, 3000), (6, 2800), (7, 2700), (8, 2600)],
+                vec![(9, 1500), (10, 1400), (11, 1300), (12, 1200)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_rating_bands_floating_several_bands() {
+        let players = vec![
+            (1, 5000),
+            (2, 4000),
+            (3, 3000),
+            (4, 2000),
+            (5, 1900),
+            (6, 1800),
+            (7, 1700),
+            (8, 1600),
+        ];
+
+        let bands = make_rating_bands(&players, 1000);
+        assert_eq!(
+            bands,
+            vec![
+                vec![(1, 5000), (2, 4000), (3, 3000), (4, 2000)],
+                vec![(5, 1900), (6, 1800), (7, 1700), (8, 1600)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_rating_bands_non_positive_width() {
+        let players = vec![
+            (1, 30),
+            (2, 20),
+            (3, 20),
+            (4, 10),
+            (5, 10),
+            (6, 10),
+            (7, 10),
+            (8, 0),
+        ];
+
+        // Width is clamped to 1, so each distinct rating starts a new band
+        let expected = make_rating_bands(&players, 1);
+        assert_eq!(make_rating_bands(&players, 0), expected);
+        assert_eq!(make_rating_bands(&players, -100), expected);
+        assert_eq!(
+            expected,
+            vec![
+                vec![(1, 30), (2, 20), (3, 20), (4, 10)],
+                vec![(5, 10), (6, 10), (7, 10), (8, 0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mcmahon_seating() {
+        let players = vec![
+            (1, 3200),
+            (2, 3100),
+            (3, 3000),
+            (4, 3010),
+            (5, 3050),
+            (6, 3150),
+            (7, 3250),
+            (8, 3020),
+            (9, 1500),
+            (10, 1400),
+            (11, 1300),
+            (12, 1200),
+            (13, 1550),
+            (14, 1450),
+            (15, 1350),
+            (16, 1250),
+        ];
+
+        let previous_seatings = vec![
+            vec![1, 2, 9, 10],
+            vec![3, 4, 11, 12],
+            vec![5, 6, 13, 14],
+            vec![7, 8, 15, 16],
+        ];
+
+        let seating = make_mcmahon_seating(
+            &players,
+            &previous_seatings,
+            1000,
+            WindPolicy::Random,
+            12345,
+        );
+        assert_eq!(seating.len(), 16);
+
+        // Top band is seated on first two tables, players never cross bands
+        let is_top_band = |id: &u32| *id <= 8;
+        seating.chunks(4).enumerate().for_each(|(index, table)| {
+            assert!(table.iter().all(|(id, _)| is_top_band(id) == (index < 2)));
+        });
+
+        // Swiss seating inside each band avoids repeats from the previous session
+        let intersections = make_intersections_table(&seating, &previous_seatings);
+        intersections.iter().for_each(|item| assert!(item.2.le(&1)));
+    }
 }
@@ -0,0 +1,341 @@
+use crate::interfaces::{ExactSwissSeating, PlayersMap, WindPolicy};
+use crate::seating_swiss::{make_played_with_matrix, swiss_seating_original};
+use crate::winds::apply_wind_policy;
+
+/// Exact swiss seating for small fields (up to ~40 players).
+/// Branch and bound search of seating with minimal total count of repeated meetings;
+/// among such seatings the one closest to swiss rating order is chosen (sum of distances
+/// between player's table and the table expected by rating).
+/// Search is limited by $max_nodes; if the limit is hit, the best found seating is returned
+/// along with the proven lower bound of repeats, so the gap is repeats - lower_bound.
+pub fn make_exact_swiss_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    max_nodes: u64,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> ExactSwissSeating {
+    if players_map.is_empty() {
+        return ExactSwissSeating {
+            seating: Vec::new(),
+            repeats: 0,
+            lower_bound: 0,
+            optimal: true,
+        };
+    }
+
+    // Players are denoted by their rank, highest rating first
+    let mut ranked_players = players_map.clone();
+    ranked_players.sort_by_key(|item| std::cmp::Reverse(item.1));
+    let players_count = ranked_players.len();
+
+    let played_with_matrix = make_played_with_matrix(players_map, previous_seatings);
+    let mut played_with = vec![0; players_count * players_count];
+    for i in 0..players_count {
+        for j in 0..players_count {
+            if i != j {
+                played_with[i * players_count + j] = played_with_matrix
+                    .get_value(ranked_players[i].0, ranked_players[j].0)
+                    .unwrap_or(0);
+            }
+        }
+    }
+
+    let mut search = ExactSwissSearch {
+        players_count,
+        played_with,
+        order: Vec::new(),
+        seated: vec![false; players_count],
+        repeats: 0,
+        displacement: 0,
+        best: None,
+        nodes: 0,
+        max_nodes,
+        aborted: false,
+    };
+    let initial = make_initial_order(&ranked_players, players_map, previous_seatings);
+    search.best = Some((search.calc_cost(&initial), initial));
+    search.search();
+
+    let root_lower_bound = search.calc_root_lower_bound();
+    let ((repeats, _), order) = search.best.unwrap();
+    let lower_bound = if search.aborted {
+        root_lower_bound.min(repeats)
+    } else {
+        repeats
+    };
+
+    let seating: PlayersMap = order.iter().map(|rank| ranked_players[*rank]).collect();
+
+    ExactSwissSeating {
+        seating: apply_wind_policy(&seating, previous_seatings, wind_policy, rand_factor),
+        repeats,
+        lower_bound,
+        optimal: repeats == lower_bound,
+    }
+}
+
+/// Heuristic swiss seating is used as initial upper bound
+fn make_initial_order(
+    ranked_players: &PlayersMap,
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+) -> Vec<usize> {
+    let ids: Vec<u32> = players_map.iter().map(|item| item.0).collect();
+    let mut played_with = make_played_with_matrix(players_map, previous_seatings);
+    let player_table = swiss_seating_original(players_map, &ids, &mut played_with);
+
+    let mut ranks: Vec<usize> = (0..ranked_players.len()).collect();
+    ranks.sort_by_key(|rank| {
+        (
+            player_table.get_value(ranked_players[*rank].0).unwrap(),
+            *rank,
+        )
+    });
+    ranks
+}
+
+/// Branch and bound state. Seating is built seat by seat: first seat at each table takes
+/// highest ranked unseated player, and players at each table are ordered by rank.
+/// Cost is (repeats, displacement), compared lexicographically.
+struct ExactSwissSearch {
+    players_count: usize,
+    played_with: Vec<u32>,
+    order: Vec<usize>,
+    seated: Vec<bool>,
+    repeats: u32,
+    displacement: u32,
+    best: Option<((u32, u32), Vec<usize>)>,
+    nodes: u64,
+    max_nodes: u64,
+    aborted: bool,
+}
+
+impl ExactSwissSearch {
+    fn search(&mut self) {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            self.aborted = true;
+            return;
+        }
+
+        let position = self.order.len();
+        if position == self.players_count {
+            self.best = Some(((self.repeats, self.displacement), self.order.clone()));
+            return;
+        }
+
+        if (
+            self.repeats,
+            self.displacement + self.calc_displacement_lower_bound(),
+        ) >= self.best.as_ref().unwrap().0
+        {
+            return;
+        }
+
+        let seat = position % 4;
+        let table_start = position - seat;
+        let candidates: Vec<usize> = if seat == 0 {
+            self.seated
+                .iter()
+                .position(|seated| !seated)
+                .into_iter()
+                .collect()
+        } else {
+            let mut candidates: Vec<usize> = (self.order[position - 1] + 1..self.players_count)
+                .filter(|rank| !self.seated[*rank])
+                .collect();
+            // Try players without repeats first to find good seatings early
+            candidates.sort_by_key(|rank| (self.calc_added_repeats(*rank, table_start), *rank));
+            candidates
+        };
+
+        for rank in candidates {
+            let added_repeats = self.calc_added_repeats(rank, table_start);
+            let added_displacement = (position / 4).abs_diff(rank / 4) as u32;
+
+            self.order.push(rank);
+            self.seated[rank] = true;
+            self.repeats += added_repeats;
+            self.displacement += added_displacement;
+
+            self.search();
+
+            self.order.pop();
+            self.seated[rank] = false;
+            self.repeats -= added_repeats;
+            self.displacement -= added_displacement;
+
+            if self.aborted {
+                return;
+            }
+        }
+    }
+
+    fn calc_added_repeats(&self, rank: usize, table_start: usize) -> u32 {
+        self.order[table_start..]
+            .iter()
+            .map(|other| self.played_with[rank * self.players_count + other])
+            .sum()
+    }
+
+    /// Unseated players can't take tables before the current one
+    fn calc_displacement_lower_bound(&self) -> u32 {
+        let table = self.order.len() / 4;
+        (0..self.players_count)
+            .filter(|rank| !self.seated[*rank])
+            .map(|rank| table.saturating_sub(rank / 4) as u32)
+            .sum()
+    }
+
+    /// Each player meets 3 opponents, so sum of 3 lowest previous meetings counts of every player
+    /// is no more than double repeats count of any seating.
+    fn calc_root_lower_bound(&self) -> u32 {
+        let sum: u32 = (0..self.players_count)
+            .map(|rank| {
+                let mut meetings: Vec<u32> = (0..self.players_count)
+                    .filter(|other| *other != rank)
+                    .map(|other| self.played_with[rank * self.players_count + other])
+                    .collect();
+                meetings.sort();
+                meetings.iter().take(3).sum::<u32>()
+            })
+            .sum();
+        sum.div_ceil(2)
+    }
+
+    fn calc_cost(&self, order: &[usize]) -> (u32, u32) {
+        let mut repeats = 0;
+        let mut displacement = 0;
+        for (table_index, table) in order.chunks(4).enumerate() {
+            for i in 0..table.len() {
+                displacement += table_index.abs_diff(table[i] / 4) as u32;
+                for j in i + 1..table.len() {
+                    repeats += self.played_with[table[i] * self.players_count + table[j]];
+                }
+            }
+        }
+        (repeats, displacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersections::make_intersections_table;
+
+    fn count_repeats(seating: &PlayersMap, previous_seatings: &[Vec<u32>]) -> u32 {
+        let played_with = make_played_with_matrix(seating, previous_seatings);
+        seating
+            .chunks(4)
+            .map(|table| {
+                let mut repeats = 0;
+                for i in 0..table.len() {
+                    for j in i + 1..table.len() {
+                        repeats += played_with.get_value(table[i].0, table[j].0).unwrap_or(0);
+                    }
+                }
+                repeats
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_exact_swiss_seating_without_history() {
+        let players: PlayersMap = (1..=12).map(|id| (id, 1000 - id as i32)).collect();
+        let result = make_exact_swiss_seating(&players, &[], 100000, WindPolicy::Fixed, 12345);
+
+        assert!(result.optimal);
+        assert_eq!(result.repeats, 0);
+        // Swiss order is kept as is
+        let mut tables: Vec<Vec<u32>> = result
+            .seating
+            .chunks(4)
+            .map(|table| {
+                let mut ids: Vec<u32> = table.iter().map(|(id, _)| *id).collect();
+                ids.sort();
+                ids
+            })
+            .collect();
+        tables.sort();
+        assert_eq!(
+            tables,
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10, 11, 12]]
+        );
+    }
+
+    #[test]
+    fn test_exact_swiss_seating_unavoidable_repeats() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1000 - id as i32)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+        let result = make_exact_swiss_seating(
+            &players,
+            &previous_seatings,
+            100000,
+            WindPolicy::Fixed,
+            12345,
+        );
+
+        // Each table has at least two pairs of players from the same previous table
+        assert!(result.optimal);
+        assert_eq!(result.repeats, 4);
+        assert_eq!(result.lower_bound, 4);
+        assert_eq!(count_repeats(&result.seating, &previous_seatings), 4);
+    }
+
+    #[test]
+    fn test_exact_swiss_seating_not_worse_than_heuristic() {
+        let players: PlayersMap = (1..=16).map(|id| (id, 2000 - (id as i32) * 7)).collect();
+        let mut previous_seatings: Vec<Vec<u32>> = Vec::new();
+
+        for session in 0..4 {
+            let heuristic = crate::seating_swiss::make_swiss_seating(
+                &players,
+                &previous_seatings,
+                WindPolicy::Fixed,
+                12345,
+            );
+            let result = make_exact_swiss_seating(
+                &players,
+                &previous_seatings,
+                200000,
+                WindPolicy::Fixed,
+                12345 + session,
+            );
+
+            assert_eq!(result.seating.len(), 16);
+            assert!(result.lower_bound <= result.repeats);
+            assert_eq!(
+                count_repeats(&result.seating, &previous_seatings),
+                result.repeats
+            );
+            assert!(result.repeats <= count_repeats(&heuristic, &previous_seatings));
+
+            previous_seatings.extend(
+                result
+                    .seating
+                    .chunks(4)
+                    .map(|table| table.iter().map(|(id, _)| *id).collect::<Vec<u32>>()),
+            );
+        }
+
+        let intersections = make_intersections_table(&players, &previous_seatings);
+        assert!(intersections.iter().all(|item| item.2 <= 2));
+    }
+
+    #[test]
+    fn test_exact_swiss_seating_nodes_limit() {
+        let players: PlayersMap = (1..=16).map(|id| (id, 2000 - id as i32)).collect();
+        let previous_seatings = vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+            vec![13, 14, 15, 16],
+        ];
+        let result =
+            make_exact_swiss_seating(&players, &previous_seatings, 1, WindPolicy::Fixed, 12345);
+
+        assert_eq!(result.seating.len(), 16);
+        assert!(result.lower_bound <= result.repeats);
+    }
+}
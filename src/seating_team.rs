@@ -0,0 +1,337 @@
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::matrix::Matrix;
+use crate::minimap::Minimap;
+use crate::shuffle::shuffle;
+use crate::winds::apply_wind_policy;
+use lcg_rand::rand::LCG;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TeamError {
+    PlayersCount {
+        count: usize,
+    }, // Players count is not a multiple of 4
+    TeamTooLarge {
+        team: u32,
+        size: usize,
+        tables: usize,
+    }, // Team has more players than tables
+}
+
+impl fmt::Display for TeamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeamError::PlayersCount { count } => {
+                write!(f, "players count should be a multiple of 4, got {count}")
+            }
+            TeamError::TeamTooLarge { team, size, tables } => write!(
+                f,
+                "team {team} has {size} players, which can't be seated at {tables} tables without teammates"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TeamError {}
+
+/// Team tournament seating
+/// $teams is a list of (player_id, team_id) pairs; players missing there are considered
+/// to be single-player teams. No two members of the same team are seated at the same table,
+/// and teams which met each other less times (including current session) are preferred at the same table.
+/// Returns error if players count is not a multiple of 4 or some team has more players than tables:
+/// otherwise players can always be seated without teammates at the same table.
+pub fn make_team_seating(
+    players_map: &PlayersMap,
+    teams: &[(u32, u32)],
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, TeamError> {
+    const MAX_ITERATIONS: usize = 100;
+    if !players_map.len().is_multiple_of(4) {
+        return Err(TeamError::PlayersCount {
+            count: players_map.len(),
+        });
+    }
+    let mut best_seating = Vec::new();
+    let mut factor = u32::MAX; // lower is better
+
+    // Players of each team; teams are keyed by index + 1, so team ids of any value
+    // can't collide with single-player teams of players without team (None)
+    let mut team_ids: Vec<Option<u32>> = Vec::new();
+    let mut team_players: Vec<PlayersMap> = Vec::new();
+    let mut player_team = Minimap::new(players_map.len());
+    for player in players_map {
+        let team = teams
+            .iter()
+            .rfind(|(id, _)| *id == player.0)
+            .map(|(_, team)| *team);
+        let index = match team_ids.iter().position(|id| team.is_some() && *id == team) {
+            Some(index) => index,
+            None => {
+                team_ids.push(team);
+                team_players.push(Vec::new());
+                team_ids.len() - 1
+            }
+        };
+        team_players[index].push(*player);
+        player_team.set_value(player.0, index as u32 + 1);
+    }
+
+    // Players without team are single-player teams, which always fit
+    let tables_count = players_map.len() / 4;
+    for (team, players) in team_ids.iter().zip(&team_players) {
+        if let Some(team) = team {
+            if players.len() > tables_count {
+                return Err(TeamError::TeamTooLarge {
+                    team: *team,
+                    size: players.len(),
+                    tables: tables_count,
+                });
+            }
+        }
+    }
+
+    let team_played_with =
+        make_team_played_with_matrix(team_ids.len(), &player_team, previous_seatings);
+
+    for i in 0..MAX_ITERATIONS {
+        let mut random: LCG = LCG::from_seed(rand_factor + (i as u64) * 17);
+        let mut attempt_played_with = team_played_with.clone();
+
+        if let Some((seating, new_factor)) =
+            make_team_tables(&team_players, &mut attempt_played_with, &mut random)
+        {
+            if new_factor < factor {
+                factor = new_factor;
+                best_seating = seating;
+            }
+        }
+    }
+
+    Ok(apply_wind_policy(
+        &best_seating,
+        previous_seatings,
+        wind_policy,
+        rand_factor,
+    ))
+}
+
+/// Count previous games played by members of each pair of teams
+fn make_team_played_with_matrix(
+    teams_count: usize,
+    player_team: &Minimap<u32>,
+    previous_seatings: &[Vec<u32>],
+) -> Matrix<u32> {
+    let mut team_played_with = Matrix::new(teams_count);
+
+    for table in previous_seatings {
+        let table_teams: Vec<u32> = table
+            .iter()
+            .filter_map(|id| player_team.get_value(*id))
+            .collect();
+        for i in 0..table_teams.len() {
+            for j in i + 1..table_teams.len() {
+                if table_teams[i] == table_teams[j] {
+                    continue;
+                }
+                team_played_with.set_value(
+                    table_teams[i],
+                    table_teams[j],
+                    1 + team_played_with
+                        .get_value(table_teams[i], table_teams[j])
+                        .unwrap_or(0),
+                );
+            }
+        }
+    }
+
+    team_played_with
+}
+
+/// Single attempt to seat teams, table by table.
+/// Returns seating and its factor (sum of previous meetings of teams at each table, lower is better),
+/// or None if attempt led to a table where teammates should meet. It doesn't happen if no team
+/// has more players than tables: teams which have a player for every table left are seated first,
+/// so after each table no team has more players than tables left.
+/// Teams are keyed in $team_played_with by their index in $team_players + 1.
+fn make_team_tables(
+    team_players: &[PlayersMap],
+    team_played_with: &mut Matrix<u32>,
+    random: &mut LCG,
+) -> Option<(PlayersMap, u32)> {
+    let mut remaining: Vec<PlayersMap> = team_players
+        .iter()
+        .map(|players| shuffle(players, random))
+        .collect();
+    // Random order of teams with equal preference
+    let tie_breaks: Vec<u64> = team_players.iter().map(|_| random.next()).collect();
+
+    let tables_count = team_players
+        .iter()
+        .map(|players| players.len())
+        .sum::<usize>()
+        / 4;
+    let mut seating = Vec::new();
+    let mut factor = 0;
+
+    for table_index in 0..tables_count {
+        let tables_left = tables_count - table_index;
+        let mut table_teams: Vec<usize> = Vec::new();
+
+        // Teams which have a player for every table left must be seated right now
+        for (team, players) in remaining.iter().enumerate() {
+            if players.len() > tables_left {
+                return None;
+            }
+            if players.len() == tables_left {
+                table_teams.push(team);
+            }
+        }
+        if table_teams.len() > 4 {
+            return None;
+        }
+
+        while table_teams.len() < 4 {
+            let next_team = (0..remaining.len())
+                .filter(|team| !remaining[*team].is_empty() && !table_teams.contains(team))
+                .min_by_key(|team| {
+                    let meetings: u32 = table_teams
+                        .iter()
+                        .map(|other| {
+                            team_played_with
+                                .get_value(*team as u32 + 1, *other as u32 + 1)
+                                .unwrap_or(0)
+                        })
+                        .sum();
+                    (
+                        meetings,
+                        std::cmp::Reverse(remaining[*team].len()),
+                        tie_breaks[*team],
+                    )
+                })?;
+            table_teams.push(next_team);
+        }
+
+        for i in 0..table_teams.len() {
+            for j in i + 1..table_teams.len() {
+                let (team1, team2) = (table_teams[i] as u32 + 1, table_teams[j] as u32 + 1);
+                let meetings = team_played_with.get_value(team1, team2).unwrap_or(0);
+                factor += meetings;
+                team_played_with.set_value(team1, team2, meetings + 1);
+            }
+        }
+
+        for team in table_teams {
+            seating.push(remaining[team].pop().unwrap());
+        }
+    }
+
+    Some((seating, factor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_teams(teams_count: u32) -> (PlayersMap, Vec<(u32, u32)>) {
+        let players: PlayersMap = (1..=teams_count * 4).map(|id| (id, 1500)).collect();
+        let teams: Vec<(u32, u32)> = (1..=teams_count * 4)
+            .map(|id| (id, (id - 1) / 4 + 1))
+            .collect();
+        (players, teams)
+    }
+
+    fn count_team_meetings(seatings: &[Vec<u32>], teams_count: u32) -> Vec<u32> {
+        let mut meetings = Vec::new();
+        for team1 in 1..=teams_count {
+            for team2 in team1 + 1..=teams_count {
+                let count = seatings
+                    .iter()
+                    .filter(|table| {
+                        table.iter().any(|id| (id - 1) / 4 + 1 == team1)
+                            && table.iter().any(|id| (id - 1) / 4 + 1 == team2)
+                    })
+                    .count();
+                meetings.push(count as u32);
+            }
+        }
+        meetings
+    }
+
+    #[test]
+    fn test_make_team_seating() {
+        let (players, teams) = make_teams(8);
+        let mut previous_seatings: Vec<Vec<u32>> = Vec::new();
+
+        for session in 0..3 {
+            let seating = make_team_seating(
+                &players,
+                &teams,
+                &previous_seatings,
+                WindPolicy::Random,
+                12345 + session,
+            )
+            .unwrap();
+            assert_eq!(seating.len(), 32);
+
+            for table in seating.chunks(4) {
+                let mut table_teams: Vec<u32> = table.iter().map(|(id, _)| (id - 1) / 4).collect();
+                table_teams.sort();
+                table_teams.dedup();
+                assert_eq!(table_teams.len(), 4);
+                previous_seatings.push(table.iter().map(|(id, _)| *id).collect());
+            }
+        }
+
+        // 3 sessions of 8 tables give 144 meetings for 28 pairs of teams
+        let meetings = count_team_meetings(&previous_seatings, 8);
+        assert!(meetings.iter().all(|count| *count >= 4 && *count <= 6));
+    }
+
+    #[test]
+    fn test_make_team_seating_impossible() {
+        let (players, mut teams) = make_teams(4);
+        // Five members of team 1 can't be seated at four tables
+        teams[4].1 = 1;
+        let error =
+            make_team_seating(&players, &teams, &[], WindPolicy::Random, 12345).unwrap_err();
+        assert_eq!(
+            error,
+            TeamError::TeamTooLarge {
+                team: 1,
+                size: 5,
+                tables: 4
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "team 1 has 5 players, which can't be seated at 4 tables without teammates"
+        );
+    }
+
+    #[test]
+    fn test_make_team_seating_players_count() {
+        let (mut players, teams) = make_teams(2);
+        players.pop();
+        assert_eq!(
+            make_team_seating(&players, &teams, &[], WindPolicy::Random, 12345),
+            Err(TeamError::PlayersCount { count: 7 })
+        );
+    }
+
+    #[test]
+    fn test_make_team_seating_large_team_ids() {
+        // Player 3 has no team, team id u32::MAX - 3 must not make player 3 a teammate
+        // of players 1 and 2: team of 3 players can't be seated at 2 tables
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let teams = vec![(1, u32::MAX - 3), (2, u32::MAX - 3)];
+
+        let seating = make_team_seating(&players, &teams, &[], WindPolicy::Fixed, 12345).unwrap();
+        assert_eq!(seating.len(), 8);
+        for table in seating.chunks(4) {
+            let ids: Vec<u32> = table.iter().map(|(id, _)| *id).collect();
+            assert!(!(ids.contains(&1) && ids.contains(&2)));
+        }
+    }
+}
@@ -0,0 +1,584 @@
+use crate::interfaces::{PlayersMap, QualityReport, WindPolicy};
+use crate::intersections::make_intersections_table;
+use crate::quality::make_quality_report;
+use crate::seating_interval::make_interval_seating;
+use crate::seating_shuffle::make_shuffled_seating;
+use crate::seating_swiss::make_cancellable_swiss_seating;
+use crate::validation::validate;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct ServerConfig {
+    pub address: String,
+    pub max_body_bytes: usize,   // Larger requests are rejected with 413
+    pub time_budget_ms: u64, // Computation time limit for single request, 503 is returned after it
+    pub max_computations: usize, // Max computations running at once, including ones over time budget
+    pub workers: usize,          // Threads handling connections
+}
+
+impl Default for ServerConfig {
+    fn default() -> ServerConfig {
+        ServerConfig {
+            address: String::from("127.0.0.1:8080"),
+            max_body_bytes: 1024 * 1024,
+            time_budget_ms: 5000,
+            max_computations: 4,
+            workers: 4,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SwissRequest {
+    pub players: PlayersMap,
+    #[serde(default)]
+    pub previous_seatings: Vec<Vec<u32>>,
+    pub wind_policy: Option<WindPolicy>, // Random if not set
+    #[serde(default)]
+    pub rand_factor: u64,
+    #[serde(default)]
+    pub strict: bool, // Reject unknown and repeated players in previous seatings too
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ShuffleRequest {
+    pub players: PlayersMap,
+    #[serde(default)]
+    pub previous_seatings: Vec<Vec<u32>>,
+    pub groups_count: Option<u32>,       // 1 if not set
+    pub wind_policy: Option<WindPolicy>, // BalancedByHistory if not set
+    #[serde(default)]
+    pub rand_factor: u64,
+    #[serde(default)]
+    pub strict: bool, // Reject unknown and repeated players in previous seatings too
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IntervalRequest {
+    pub players: PlayersMap,
+    pub step: usize,
+    pub wind_policy: Option<WindPolicy>, // Random if not set
+    #[serde(default)]
+    pub rand_factor: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IntersectionsRequest {
+    pub seating: PlayersMap,
+    #[serde(default)]
+    pub previous_seatings: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub strict: bool, // Reject unknown and repeated players in previous seatings too
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct QualityRequest {
+    pub seating: PlayersMap,
+    #[serde(default)]
+    pub previous_seatings: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub strict: bool, // Reject unknown and repeated players in previous seatings too
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SeatingResponse {
+    pub seating: PlayersMap,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IntersectionsResponse {
+    pub intersections: Vec<(u32, u32, u32)>, // (player1, player2, games played together)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// HTTP status and JSON body
+pub type Response = (u16, String);
+
+/// Start server and handle requests until the process is stopped
+pub fn run(config: ServerConfig) -> Result<(), String> {
+    let server = Arc::new(tiny_http::Server::http(&config.address).map_err(|e| e.to_string())?);
+    let running = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<thread::JoinHandle<()>> = (0..config.workers.max(1))
+        .map(|_| {
+            let server = server.clone();
+            let running = running.clone();
+            let config = config.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle_http_request(request, &config, &running);
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().map_err(|_| "worker thread panicked")?;
+    }
+    Ok(())
+}
+
+fn handle_http_request(
+    mut request: tiny_http::Request,
+    config: &ServerConfig,
+    running: &Arc<AtomicUsize>,
+) {
+    let (status, body) = if *request.method() != tiny_http::Method::Post {
+        error_response(405, "only POST requests are supported")
+    } else if request
+        .body_length()
+        .is_some_and(|length| length > config.max_body_bytes)
+    {
+        error_response(413, "request body is too large")
+    } else {
+        // Body length may be unknown for chunked requests, so reading is limited too
+        let mut body = Vec::new();
+        let limit = config.max_body_bytes as u64 + 1;
+        match request.as_reader().take(limit).read_to_end(&mut body) {
+            Err(_) => error_response(400, "can't read request body"),
+            Ok(_) if body.len() > config.max_body_bytes => {
+                error_response(413, "request body is too large")
+            }
+            Ok(_) => {
+                let path = request.url().to_string();
+                handle_request(&path, &body, config, running)
+            }
+        }
+    };
+
+    let content_type =
+        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let response = tiny_http::Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+/// Computation gets a flag which is set after time budget is over
+type Computation = Box<dyn FnOnce(&AtomicBool) -> Response + Send>;
+
+/// Route request by path and compute seating within time budget.
+/// Query string is ignored.
+pub fn handle_request(
+    path: &str,
+    body: &[u8],
+    config: &ServerConfig,
+    running: &Arc<AtomicUsize>,
+) -> Response {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let computation: Computation = match path {
+        "/swiss" => match parse_request::<SwissRequest>(body) {
+            Ok(request) => Box::new(move |cancel| {
+                match make_cancellable_swiss_seating(
+                    &request.players,
+                    &request.previous_seatings,
+                    request.wind_policy.unwrap_or(WindPolicy::Random),
+                    request.rand_factor,
+                    cancel,
+                ) {
+                    Some(result) => seating_response(result.seating),
+                    None => time_budget_response(),
+                }
+            }),
+            Err(response) => return response,
+        },
+        "/shuffle" => match parse_request::<ShuffleRequest>(body) {
+            Ok(request) if request.groups_count == Some(0) => {
+                return error_response(400, "groups_count should be positive")
+            }
+            Ok(request) => Box::new(move |_| {
+                seating_response(make_shuffled_seating(
+                    &request.players,
+                    &request.previous_seatings,
+                    request.groups_count.unwrap_or(1),
+                    request.wind_policy.unwrap_or(WindPolicy::BalancedByHistory),
+                    request.rand_factor,
+                ))
+            }),
+            Err(response) => return response,
+        },
+        "/interval" => match parse_request::<IntervalRequest>(body) {
+            Ok(request) if request.step == 0 => {
+                return error_response(400, "step should be positive")
+            }
+            Ok(request) => Box::new(move |_| {
+                seating_response(make_interval_seating(
+                    &request.players,
+                    request.step,
+                    request.wind_policy.unwrap_or(WindPolicy::Random),
+                    request.rand_factor,
+                ))
+            }),
+            Err(response) => return response,
+        },
+        "/intersections" => match parse_request::<IntersectionsRequest>(body) {
+            Ok(request) => Box::new(move |_| {
+                let response = IntersectionsResponse {
+                    intersections: make_intersections_table(
+                        &request.seating,
+                        &request.previous_seatings,
+                    ),
+                };
+                (200, serde_json::to_string(&response).unwrap())
+            }),
+            Err(response) => return response,
+        },
+        "/quality" => match parse_request::<QualityRequest>(body) {
+            Ok(request) => Box::new(move |_| {
+                let response: QualityReport =
+                    make_quality_report(&request.seating, &request.previous_seatings);
+                (200, serde_json::to_string(&response).unwrap())
+            }),
+            Err(response) => return response,
+        },
+        _ => return error_response(404, "unknown endpoint"),
+    };
+
+    run_with_time_budget(computation, config, running)
+}
+
+/// Run computation in a separate thread and stop waiting for it after time budget.
+/// After time budget computation is cancelled. Swiss seating stops on cancel, while other
+/// computations are bounded and run to the end: until then they count against max_computations.
+fn run_with_time_budget(
+    computation: Computation,
+    config: &ServerConfig,
+    running: &Arc<AtomicUsize>,
+) -> Response {
+    let slot = match ComputationSlot::acquire(running, config.max_computations) {
+        Some(slot) => slot,
+        None => return error_response(503, "server is busy, try again later"),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_computation = cancel.clone();
+    thread::spawn(move || {
+        // Slot is released before response is sent, or on unwinding if computation panics
+        let response = computation(&cancel_computation);
+        drop(slot);
+        let _ = sender.send(response);
+    });
+
+    match receiver.recv_timeout(Duration::from_millis(config.time_budget_ms)) {
+        Ok(response) => response,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            cancel.store(true, Ordering::Relaxed);
+            time_budget_response()
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => error_response(500, "seating failed"),
+    }
+}
+
+/// Place in max_computations limit, released when dropped
+struct ComputationSlot {
+    running: Arc<AtomicUsize>,
+}
+
+impl ComputationSlot {
+    fn acquire(running: &Arc<AtomicUsize>, max_computations: usize) -> Option<ComputationSlot> {
+        let taken = running.fetch_add(1, Ordering::SeqCst);
+        let slot = ComputationSlot {
+            running: running.clone(),
+        };
+        // Slot over the limit is released on drop
+        (taken < max_computations).then_some(slot)
+    }
+}
+
+impl Drop for ComputationSlot {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Parse JSON request and check players and previous seatings. Unknown and repeated
+/// players in previous seatings are rejected only for strict requests.
+fn parse_request<T>(body: &[u8]) -> Result<T, Response>
+where
+    T: for<'a> Deserialize<'a> + HasPlayers,
+{
+    let request: T = serde_json::from_slice(body)
+        .map_err(|e| error_response(400, &format!("invalid request: {e}")))?;
+    let messages: Vec<String> = validate(request.players(), request.previous_seatings())
+        .iter()
+        .filter(|diagnostic| request.strict() || diagnostic.is_fatal())
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    if !messages.is_empty() {
+        return Err(error_response(400, &messages.join("; ")));
+    }
+    Ok(request)
+}
+
+trait HasPlayers {
+    fn players(&self) -> &PlayersMap;
+    fn previous_seatings(&self) -> &[Vec<u32>] {
+        &[]
+    }
+    fn strict(&self) -> bool {
+        false
+    }
+}
+
+impl HasPlayers for SwissRequest {
+    fn players(&self) -> &PlayersMap {
+        &self.players
+    }
+    fn previous_seatings(&self) -> &[Vec<u32>] {
+        &self.previous_seatings
+    }
+    fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+impl HasPlayers for ShuffleRequest {
+    fn players(&self) -> &PlayersMap {
+        &self.players
+    }
+    fn previous_seatings(&self) -> &[Vec<u32>] {
+        &self.previous_seatings
+    }
+    fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+impl HasPlayers for IntervalRequest {
+    fn players(&self) -> &PlayersMap {
+        &self.players
+    }
+}
+
+impl HasPlayers for IntersectionsRequest {
+    fn players(&self) -> &PlayersMap {
+        &self.seating
+    }
+    fn previous_seatings(&self) -> &[Vec<u32>] {
+        &self.previous_seatings
+    }
+    fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+impl HasPlayers for QualityRequest {
+    fn players(&self) -> &PlayersMap {
+        &self.seating
+    }
+    fn previous_seatings(&self) -> &[Vec<u32>] {
+        &self.previous_seatings
+    }
+    fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+fn seating_response(seating: PlayersMap) -> Response {
+    (
+        200,
+        serde_json::to_string(&SeatingResponse { seating }).unwrap(),
+    )
+}
+
+fn time_budget_response() -> Response {
+    error_response(503, "seating was not computed within time budget")
+}
+
+fn error_response(status: u16, message: &str) -> Response {
+    let response = ErrorResponse {
+        error: message.to_string(),
+    };
+    (status, serde_json::to_string(&response).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(path: &str, body: &str) -> Response {
+        handle_request(
+            path,
+            body.as_bytes(),
+            &ServerConfig::default(),
+            &Arc::new(AtomicUsize::new(0)),
+        )
+    }
+
+    #[test]
+    fn test_handle_seating_requests() {
+        let players = "[[1,1500],[2,1400],[3,1300],[4,1200],[5,1100],[6,1000],[7,900],[8,800]]";
+
+        let (status, body) = handle(
+            "/swiss",
+            &format!(
+                r#"{{"players":{players},"previous_seatings":[[1,2,3,4],[5,6,7,8]],"wind_policy":"Fixed"}}"#
+            ),
+        );
+        assert_eq!(status, 200);
+        let response: SeatingResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.seating.len(), 8);
+
+        let (status, _) = handle("/shuffle", &format!(r#"{{"players":{players}}}"#));
+        assert_eq!(status, 200);
+
+        // Query string is not a part of endpoint
+        let (status, _) = handle(
+            "/shuffle?source=test",
+            &format!(r#"{{"players":{players}}}"#),
+        );
+        assert_eq!(status, 200);
+
+        let (status, body) = handle("/interval", &format!(r#"{{"players":{players},"step":2}}"#));
+        assert_eq!(status, 200);
+        let response: SeatingResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.seating.len(), 8);
+        // Winds are random by default, as before wind policies were introduced
+        let players: PlayersMap = serde_json::from_str(players).unwrap();
+        assert_eq!(
+            response.seating,
+            make_interval_seating(&players, 2, WindPolicy::Random, 0)
+        );
+
+        let (status, body) = handle(
+            "/intersections",
+            r#"{"seating":[[1,0],[2,0],[3,0],[4,0]],"previous_seatings":[[1,2,3,4]]}"#,
+        );
+        assert_eq!(status, 200);
+        let response: IntersectionsResponse = serde_json::from_str(&body).unwrap();
+        assert!(response.intersections.iter().all(|item| item.2 == 2));
+
+        let (status, body) = handle(
+            "/quality",
+            r#"{"seating":[[1,1600],[2,1500],[5,1400],[6,1300],[3,1200],[4,1100],[7,1000],[8,900]],
+                "previous_seatings":[[1,2,3,4],[5,6,7,8]]}"#,
+        );
+        assert_eq!(status, 200);
+        let response: QualityReport = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.repeats, 4);
+        assert_eq!(response.max_repeats, 1);
+        assert_eq!(response.max_rating_spread, 300);
+    }
+
+    #[test]
+    fn test_handle_invalid_requests() {
+        assert_eq!(handle("/unknown", "{}").0, 404);
+        assert_eq!(handle("/unknown?path=/swiss", "{}").0, 404);
+        assert_eq!(handle("/swiss", "not json").0, 400);
+        assert_eq!(handle("/swiss", r#"{"players":[[1,0],[2,0]]}"#).0, 400);
+        assert_eq!(
+            handle(
+                "/swiss",
+                r#"{"players":[[1,0],[2,0],[3,0],[4,0]],"previous_seatings":[[1,2]]}"#
+            )
+            .0,
+            400
+        );
+        assert_eq!(
+            handle(
+                "/interval",
+                r#"{"players":[[1,0],[2,0],[3,0],[4,0]],"step":0}"#
+            )
+            .0,
+            400
+        );
+    }
+
+    #[test]
+    fn test_handle_strict_requests() {
+        let body =
+            r#"{"players":[[1,0],[2,0],[3,0],[4,0]],"previous_seatings":[[1,2,3,42]]STRICT}"#;
+        assert_eq!(handle("/swiss", &body.replace("STRICT", "")).0, 200);
+
+        let (status, body) = handle("/swiss", &body.replace("STRICT", r#","strict":true"#));
+        assert_eq!(status, 400);
+        assert!(body.contains("unknown player 42"));
+    }
+
+    #[test]
+    fn test_run_with_time_budget() {
+        let config = ServerConfig {
+            time_budget_ms: 10,
+            max_computations: 1,
+            ..ServerConfig::default()
+        };
+        let running = Arc::new(AtomicUsize::new(0));
+
+        let (status, _) = run_with_time_budget(
+            Box::new(|_| {
+                thread::sleep(Duration::from_millis(500));
+                (200, String::new())
+            }),
+            &config,
+            &running,
+        );
+        assert_eq!(status, 503);
+
+        // Computation over budget is still running, so there is no room for another one
+        let (status, body) =
+            run_with_time_budget(Box::new(|_| (200, String::new())), &config, &running);
+        assert_eq!(status, 503);
+        assert!(body.contains("busy"));
+    }
+
+    #[test]
+    fn test_run_with_time_budget_cancel() {
+        let config = ServerConfig {
+            time_budget_ms: 10,
+            max_computations: 1,
+            ..ServerConfig::default()
+        };
+        let running = Arc::new(AtomicUsize::new(0));
+
+        // Computation is cancelled after time budget, so its slot is released
+        let (status, body) = run_with_time_budget(
+            Box::new(|cancel| {
+                while !cancel.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                (200, String::new())
+            }),
+            &config,
+            &running,
+        );
+        assert_eq!(status, 503);
+        assert!(body.contains("time budget"));
+        wait_for_slots(&running);
+
+        // Panicking computation releases its slot too. Budget is larger, so it's not
+        // over before panic is reported
+        let config = ServerConfig {
+            time_budget_ms: 5000,
+            ..config
+        };
+        let (status, _) =
+            run_with_time_budget(Box::new(|_| panic!("seating failed")), &config, &running);
+        assert_eq!(status, 500);
+        wait_for_slots(&running);
+
+        let (status, _) =
+            run_with_time_budget(Box::new(|_| (200, String::new())), &config, &running);
+        assert_eq!(status, 200);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    fn wait_for_slots(running: &Arc<AtomicUsize>) {
+        for _ in 0..1000 {
+            if running.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("computation slot is not released");
+    }
+}
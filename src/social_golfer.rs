@@ -0,0 +1,262 @@
+use crate::designs::{make_design, make_seatings_from_rounds};
+use crate::interfaces::{GolferSchedule, PlayersMap};
+use crate::shuffle::shuffle;
+use lcg_rand::rand::LCG;
+use std::time::{Duration, Instant};
+
+/// Social golfer solver: find as many sessions as possible where no pair of players meets twice.
+/// Search starts from known design for players count (see get_design_sessions_count) if there is one,
+/// then tries to add more sessions with depth-first search until max_sessions are found
+/// or time budget is over. Players count should be a multiple of 4, otherwise no sessions are returned.
+/// Useful to plan how many sessions can be played without repeated meetings.
+pub fn make_social_golfer_schedule(
+    players_map: &PlayersMap,
+    max_sessions: usize,
+    time_budget_ms: u64,
+    rand_factor: u64,
+) -> GolferSchedule {
+    let players_count = players_map.len();
+    if players_count == 0 || !players_count.is_multiple_of(4) || max_sessions == 0 {
+        return GolferSchedule {
+            sessions: Vec::new(),
+            proven_max: false,
+        };
+    }
+
+    // Each session a player meets 3 new opponents
+    let upper_bound = (players_count - 1) / 3;
+    let target = max_sessions.min(upper_bound);
+
+    let design = make_design(players_count).unwrap_or_default();
+    let seeded = !design.is_empty();
+    let initial_rounds: Vec<Vec<u32>> = if seeded {
+        design
+            .iter()
+            .take(target)
+            .map(|round| round.iter().flatten().copied().collect())
+            .collect()
+    } else {
+        // Any first session is equivalent to this one up to renaming of players
+        vec![(0..players_count as u32).collect()]
+    };
+
+    let mut search = GolferSearch::new(
+        players_count,
+        initial_rounds,
+        target,
+        Instant::now() + Duration::from_millis(time_budget_ms),
+    );
+    if search.best.len() < target {
+        search.search();
+    }
+
+    let rounds: Vec<Vec<Vec<u32>>> = search
+        .best
+        .iter()
+        .map(|round| round.chunks(4).map(|table| table.to_vec()).collect())
+        .collect();
+    let mut random: LCG = LCG::from_seed(rand_factor);
+    let shuffled_players = shuffle(players_map, &mut random);
+
+    GolferSchedule {
+        proven_max: rounds.len() == upper_bound
+            || (!seeded && !search.timed_out && rounds.len() < target),
+        sessions: make_seatings_from_rounds(&rounds, &shuffled_players),
+    }
+}
+
+/// Depth-first search state. Players are denoted by indices 0..players_count,
+/// each round is a flat list of players, 4 per table.
+struct GolferSearch {
+    players_count: usize,
+    met: Vec<bool>,
+    rounds: Vec<Vec<u32>>,
+    current: Vec<u32>,
+    seated: Vec<bool>,
+    best: Vec<Vec<u32>>,
+    target: usize,
+    deadline: Instant,
+    nodes: u64,
+    timed_out: bool,
+}
+
+impl GolferSearch {
+    fn new(
+        players_count: usize,
+        initial_rounds: Vec<Vec<u32>>,
+        target: usize,
+        deadline: Instant,
+    ) -> GolferSearch {
+        let mut met = vec![false; players_count * players_count];
+        for round in &initial_rounds {
+            for table in round.chunks(4) {
+                for i in 0..table.len() {
+                    for j in i + 1..table.len() {
+                        let (a, b) = (table[i] as usize, table[j] as usize);
+                        met[a * players_count + b] = true;
+                        met[b * players_count + a] = true;
+                    }
+                }
+            }
+        }
+
+        GolferSearch {
+            players_count,
+            met,
+            best: initial_rounds.clone(),
+            rounds: initial_rounds,
+            current: Vec::new(),
+            seated: vec![false; players_count],
+            target,
+            deadline,
+            nodes: 0,
+            timed_out: false,
+        }
+    }
+
+    /// Returns true if search should be stopped: target is reached or time is over
+    fn search(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(1024) && Instant::now() >= self.deadline {
+            self.timed_out = true;
+            return true;
+        }
+
+        if self.current.len() == self.players_count {
+            return self.search_next_round();
+        }
+
+        let position = self.current.len();
+        let seat = position % 4;
+        let table_start = position - seat;
+
+        // Symmetry breaking: tables are ordered by their first player, which is the lowest
+        // unseated one, and players at each table are ordered by index.
+        let first_candidate = if seat == 0 {
+            self.seated.iter().position(|seated| !seated).unwrap()
+        } else {
+            self.current[position - 1] as usize + 1
+        };
+        let last_candidate = if seat == 0 {
+            first_candidate + 1
+        } else {
+            self.players_count
+        };
+
+        for candidate in first_candidate..last_candidate {
+            if self.seated[candidate]
+                || self.current[table_start..]
+                    .iter()
+                    .any(|other| self.met[*other as usize * self.players_count + candidate])
+            {
+                continue;
+            }
+
+            self.place(candidate as u32, table_start);
+            let stop = self.search();
+            self.unplace(table_start);
+            if stop {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn search_next_round(&mut self) -> bool {
+        let round = std::mem::take(&mut self.current);
+        let seated = std::mem::replace(&mut self.seated, vec![false; self.players_count]);
+        self.rounds.push(round);
+        if self.rounds.len() > self.best.len() {
+            self.best = self.rounds.clone();
+        }
+
+        let stop = self.rounds.len() >= self.target || self.search();
+
+        self.current = self.rounds.pop().unwrap();
+        self.seated = seated;
+        stop
+    }
+
+    fn place(&mut self, player: u32, table_start: usize) {
+        for other in &self.current[table_start..] {
+            let (a, b) = (*other as usize, player as usize);
+            self.met[a * self.players_count + b] = true;
+            self.met[b * self.players_count + a] = true;
+        }
+        self.seated[player as usize] = true;
+        self.current.push(player);
+    }
+
+    fn unplace(&mut self, table_start: usize) {
+        let player = self.current.pop().unwrap();
+        for other in &self.current[table_start..] {
+            let (a, b) = (*other as usize, player as usize);
+            self.met[a * self.players_count + b] = false;
+            self.met[b * self.players_count + a] = false;
+        }
+        self.seated[player as usize] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_no_repeats(sessions: &[PlayersMap]) {
+        let mut met: Vec<(u32, u32)> = Vec::new();
+        for session in sessions {
+            for table in session.chunks(4) {
+                for i in 0..4 {
+                    for j in i + 1..4 {
+                        let pair = (table[i].0.min(table[j].0), table[i].0.max(table[j].0));
+                        assert!(!met.contains(&pair));
+                        met.push(pair);
+                    }
+                }
+            }
+        }
+    }
+
+    fn make_players(count: u32) -> PlayersMap {
+        (1..=count).map(|id| (id, 1500)).collect()
+    }
+
+    #[test]
+    fn test_social_golfer_exhaustive() {
+        // Second session of 8 players always repeats a pair
+        let schedule = make_social_golfer_schedule(&make_players(8), 10, 1000, 12345);
+        assert_eq!(schedule.sessions.len(), 1);
+        assert!(schedule.proven_max);
+
+        let schedule = make_social_golfer_schedule(&make_players(12), 10, 1000, 12345);
+        assert_no_repeats(&schedule.sessions);
+        assert!(schedule.proven_max);
+    }
+
+    #[test]
+    fn test_social_golfer_from_design() {
+        let schedule = make_social_golfer_schedule(&make_players(16), 10, 1000, 12345);
+        assert_eq!(schedule.sessions.len(), 5);
+        assert!(schedule.proven_max);
+        assert_no_repeats(&schedule.sessions);
+
+        let schedule = make_social_golfer_schedule(&make_players(16), 3, 1000, 12345);
+        assert_eq!(schedule.sessions.len(), 3);
+        assert!(!schedule.proven_max);
+    }
+
+    #[test]
+    fn test_social_golfer_search() {
+        let schedule = make_social_golfer_schedule(&make_players(24), 4, 2000, 12345);
+        assert_eq!(schedule.sessions.len(), 4);
+        assert!(schedule.sessions.iter().all(|session| session.len() == 24));
+        assert_no_repeats(&schedule.sessions);
+    }
+
+    #[test]
+    fn test_social_golfer_invalid_players_count() {
+        let schedule = make_social_golfer_schedule(&make_players(10), 10, 1000, 12345);
+        assert!(schedule.sessions.is_empty());
+    }
+}
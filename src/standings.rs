@@ -0,0 +1,87 @@
+use crate::interfaces::{PlayersMap, Standing, TieBreak};
+use std::cmp::Ordering;
+
+/// Compare standings of two players: Less means $a is ranked higher than $b.
+/// Points are compared first, then tie-breaks in given order, then ids.
+pub fn compare_standings(a: &Standing, b: &Standing, tie_breaks: &[TieBreak]) -> Ordering {
+    b.points
+        .cmp(&a.points)
+        .then_with(|| {
+            tie_breaks
+                .iter()
+                .map(|tie_break| match tie_break {
+                    TieBreak::Uma => b.uma.cmp(&a.uma),
+                    TieBreak::Placements => b.placements[..3].cmp(&a.placements[..3]),
+                    TieBreak::FewerLastPlaces => a.placements[3].cmp(&b.placements[3]),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Make players map where rating is a unique rank key: top ranked player gets the highest key.
+/// This way algorithms comparing ratings follow standings order with no ties.
+pub(crate) fn make_rank_keys(standings: &[Standing], tie_breaks: &[TieBreak]) -> PlayersMap {
+    let mut sorted_standings = standings.to_vec();
+    sorted_standings.sort_by(|a, b| compare_standings(a, b, tie_breaks));
+
+    sorted_standings
+        .iter()
+        .enumerate()
+        .map(|(index, standing)| (standing.id, (sorted_standings.len() - index) as i32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_standing(id: u32, points: i32, uma: i32, placements: [u32; 4]) -> Standing {
+        Standing {
+            id,
+            points,
+            uma,
+            placements,
+        }
+    }
+
+    #[test]
+    fn test_compare_standings() {
+        let a = make_standing(1, 100, 30, [1, 0, 0, 1]);
+        let b = make_standing(2, 100, 20, [2, 0, 0, 0]);
+
+        assert_eq!(compare_standings(&a, &b, &[]), Ordering::Less);
+        assert_eq!(compare_standings(&a, &b, &[TieBreak::Uma]), Ordering::Less);
+        assert_eq!(
+            compare_standings(&a, &b, &[TieBreak::Placements, TieBreak::Uma]),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_standings(&a, &b, &[TieBreak::FewerLastPlaces]),
+            Ordering::Greater
+        );
+
+        let c = make_standing(3, 120, -50, [0, 0, 0, 2]);
+        assert_eq!(compare_standings(&c, &a, &[TieBreak::Uma]), Ordering::Less);
+    }
+
+    #[test]
+    fn test_make_rank_keys() {
+        let standings = vec![
+            make_standing(4, 0, 10, [0, 1, 1, 0]),
+            make_standing(1, 50, 0, [1, 0, 0, 1]),
+            make_standing(3, 0, 10, [1, 0, 0, 1]),
+            make_standing(2, 0, -10, [0, 0, 1, 1]),
+        ];
+
+        assert_eq!(
+            make_rank_keys(&standings, &[TieBreak::Uma, TieBreak::Placements]),
+            vec![(1, 4), (3, 3), (4, 2), (2, 1)]
+        );
+        assert_eq!(
+            make_rank_keys(&standings, &[TieBreak::FewerLastPlaces]),
+            vec![(1, 4), (4, 3), (2, 2), (3, 1)]
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! Seating functions which validate their input and parameters first and return all problems
+//! found instead of seating, see validate. Finals seating is not included: it takes standings
+//! of any length and seats only advancing players.
+
+use crate::designs;
+use crate::interfaces::{
+    BalancedSeating, ExactSwissSeating, GolferSchedule, IntervalConfig, PlayersMap, Standing,
+    TieBreak, WindPolicy,
+};
+use crate::intersections;
+use crate::seating_balanced;
+use crate::seating_interval;
+use crate::seating_pairs;
+use crate::seating_shuffle;
+use crate::seating_snake;
+use crate::seating_snake::SnakeError;
+use crate::seating_swiss;
+use crate::seating_swiss_exact;
+use crate::seating_team;
+use crate::seating_team::TeamError;
+use crate::social_golfer;
+use crate::validation::{validate, Diagnostic};
+
+/// Validate players, previous seatings and checks of parameters, then make seating
+fn run<T>(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    parameters: impl IntoIterator<Item = Option<Diagnostic>>,
+    make_seating: impl FnOnce() -> T,
+) -> Result<T, Vec<Diagnostic>> {
+    let mut diagnostics = validate(players_map, previous_seatings);
+    diagnostics.extend(parameters.into_iter().flatten());
+    if diagnostics.is_empty() {
+        Ok(make_seating())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn check_positive(name: &'static str, value: i64) -> Option<Diagnostic> {
+    (value <= 0).then(|| Diagnostic::InvalidParameter {
+        name,
+        reason: format!("should be positive, got {value}"),
+    })
+}
+
+fn check_weight(name: &'static str, value: f64) -> Option<Diagnostic> {
+    (!value.is_finite() || value < 0.0).then(|| Diagnostic::InvalidParameter {
+        name,
+        reason: format!("should be a non-negative number, got {value}"),
+    })
+}
+
+/// Players count is validated before snake seating, so its error is reported the same way
+fn snake_error(error: SnakeError) -> Vec<Diagnostic> {
+    match error {
+        SnakeError::PlayersCount { count } => vec![Diagnostic::PlayersCount { count }],
+    }
+}
+
+/// See seating_swiss::make_swiss_seating
+pub fn make_swiss_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    run(players_map, previous_seatings, [], || {
+        seating_swiss::make_swiss_seating(players_map, previous_seatings, wind_policy, rand_factor)
+    })
+}
+
+/// See seating_swiss::make_swiss_seating_by_standings
+pub fn make_swiss_seating_by_standings(
+    standings: &[Standing],
+    tie_breaks: &[TieBreak],
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let players_map: PlayersMap = standings
+        .iter()
+        .map(|standing| (standing.id, standing.points))
+        .collect();
+    run(&players_map, previous_seatings, [], || {
+        seating_swiss::make_swiss_seating_by_standings(
+            standings,
+            tie_breaks,
+            previous_seatings,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See seating_swiss::make_mcmahon_seating, non-positive $band_width is rejected
+pub fn make_mcmahon_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    band_width: i32,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let parameters = [check_positive("band_width", band_width as i64)];
+    run(players_map, previous_seatings, parameters, || {
+        seating_swiss::make_mcmahon_seating(
+            players_map,
+            previous_seatings,
+            band_width,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See seating_swiss_exact::make_exact_swiss_seating
+pub fn make_exact_swiss_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    max_nodes: u64,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<ExactSwissSeating, Vec<Diagnostic>> {
+    run(players_map, previous_seatings, [], || {
+        seating_swiss_exact::make_exact_swiss_seating(
+            players_map,
+            previous_seatings,
+            max_nodes,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See seating_shuffle::make_shuffled_seating
+pub fn make_shuffled_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    groups_count: u32,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let parameters = [check_positive("groups_count", groups_count as i64)];
+    run(players_map, previous_seatings, parameters, || {
+        seating_shuffle::make_shuffled_seating(
+            players_map,
+            previous_seatings,
+            groups_count,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See seating_snake::make_snake_seating
+pub fn make_snake_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    run(players_map, previous_seatings, [], || {
+        seating_snake::make_snake_seating(players_map, previous_seatings, wind_policy, rand_factor)
+    })?
+    .map_err(snake_error)
+}
+
+/// See seating_balanced::make_balanced_seating
+pub fn make_balanced_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    rating_weight: f64,
+    repeats_weight: f64,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<BalancedSeating, Vec<Diagnostic>> {
+    let parameters = [
+        check_weight("rating_weight", rating_weight),
+        check_weight("repeats_weight", repeats_weight),
+    ];
+    run(players_map, previous_seatings, parameters, || {
+        seating_balanced::make_balanced_seating(
+            players_map,
+            previous_seatings,
+            rating_weight,
+            repeats_weight,
+            wind_policy,
+            rand_factor,
+        )
+    })?
+    .map_err(snake_error)
+}
+
+/// See seating_team::make_team_seating
+pub fn make_team_seating(
+    players_map: &PlayersMap,
+    teams: &[(u32, u32)],
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    run(players_map, previous_seatings, [], || {
+        seating_team::make_team_seating(
+            players_map,
+            teams,
+            previous_seatings,
+            wind_policy,
+            rand_factor,
+        )
+    })?
+    .map_err(|error| match error {
+        TeamError::PlayersCount { count } => vec![Diagnostic::PlayersCount { count }],
+        TeamError::TeamTooLarge { .. } => vec![Diagnostic::InvalidParameter {
+            name: "teams",
+            reason: error.to_string(),
+        }],
+    })
+}
+
+/// See seating_pairs::make_pairs_seating
+pub fn make_pairs_seating(
+    players_map: &PlayersMap,
+    pairs: &[(u32, u32)],
+    previous_seatings: &[Vec<u32>],
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    run(players_map, previous_seatings, [], || {
+        seating_pairs::make_pairs_seating(players_map, pairs, previous_seatings, rand_factor)
+    })?
+    .map_err(|error| {
+        vec![Diagnostic::InvalidParameter {
+            name: "pairs",
+            reason: error.to_string(),
+        }]
+    })
+}
+
+/// See seating_interval::make_interval_seating
+pub fn make_interval_seating(
+    current_rating_list: &PlayersMap,
+    step: usize,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let parameters = [check_positive("step", step as i64)];
+    run(current_rating_list, &[], parameters, || {
+        seating_interval::make_interval_seating(current_rating_list, step, wind_policy, rand_factor)
+    })
+}
+
+/// See seating_interval::make_interval_seating_with_history
+pub fn make_interval_seating_with_history(
+    current_rating_list: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    step: usize,
+    max_displacement: usize,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let parameters = [check_positive("step", step as i64)];
+    run(current_rating_list, previous_seatings, parameters, || {
+        seating_interval::make_interval_seating_with_history(
+            current_rating_list,
+            previous_seatings,
+            step,
+            max_displacement,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See seating_interval::make_configured_interval_seating
+pub fn make_configured_interval_seating(
+    current_rating_list: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    config: &IntervalConfig,
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> Result<PlayersMap, Vec<Diagnostic>> {
+    let parameters = config
+        .segments
+        .iter()
+        .map(|segment| check_positive("segment step", segment.step as i64));
+    run(current_rating_list, previous_seatings, parameters, || {
+        seating_interval::make_configured_interval_seating(
+            current_rating_list,
+            previous_seatings,
+            config,
+            wind_policy,
+            rand_factor,
+        )
+    })
+}
+
+/// See designs::make_scheduled_seatings
+pub fn make_scheduled_seatings(
+    players_map: &PlayersMap,
+    sessions_count: usize,
+    rand_factor: u64,
+) -> Result<Vec<PlayersMap>, Vec<Diagnostic>> {
+    run(players_map, &[], [], || {
+        designs::make_scheduled_seatings(players_map, sessions_count, rand_factor)
+    })
+}
+
+/// See social_golfer::make_social_golfer_schedule
+pub fn make_social_golfer_schedule(
+    players_map: &PlayersMap,
+    max_sessions: usize,
+    time_budget_ms: u64,
+    rand_factor: u64,
+) -> Result<GolferSchedule, Vec<Diagnostic>> {
+    run(players_map, &[], [], || {
+        social_golfer::make_social_golfer_schedule(
+            players_map,
+            max_sessions,
+            time_budget_ms,
+            rand_factor,
+        )
+    })
+}
+
+/// See intersections::make_intersections_table
+pub fn make_intersections_table(
+    seating: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+) -> Result<Vec<(u32, u32, u32)>, Vec<Diagnostic>> {
+    run(seating, previous_seatings, [], || {
+        intersections::make_intersections_table(seating, previous_seatings)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_seating() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1600 - id as i32 * 10)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+
+        assert_eq!(
+            make_swiss_seating(&players, &previous_seatings, WindPolicy::Fixed, 12345),
+            Ok(seating_swiss::make_swiss_seating(
+                &players,
+                &previous_seatings,
+                WindPolicy::Fixed,
+                12345
+            ))
+        );
+
+        let previous_seatings = vec![vec![1, 2, 3, 42], vec![5, 5, 7, 8]];
+        assert_eq!(
+            make_shuffled_seating(&players, &previous_seatings, 1, WindPolicy::Fixed, 12345),
+            Err(vec![
+                Diagnostic::UnknownPlayer { table: 0, id: 42 },
+                Diagnostic::SelfPairing { table: 1, id: 5 },
+            ])
+        );
+        assert_eq!(
+            make_interval_seating(&players[..6].to_vec(), 1, WindPolicy::Fixed, 12345),
+            Err(vec![Diagnostic::PlayersCount { count: 6 }])
+        );
+        assert_eq!(
+            make_pairs_seating(&players, &[(1, 2), (3, 4), (5, 6)], &[], 12345),
+            Err(vec![Diagnostic::InvalidParameter {
+                name: "pairs",
+                reason: String::from("pairs count should be even, got 3"),
+            }])
+        );
+        // Team of 3 players can't be seated at 2 tables
+        assert_eq!(
+            make_team_seating(
+                &players,
+                &[(1, 7), (2, 7), (3, 7)],
+                &[],
+                WindPolicy::Fixed,
+                12345
+            ),
+            Err(vec![Diagnostic::InvalidParameter {
+                name: "teams",
+                reason: String::from(
+                    "team 7 has 3 players, which can't be seated at 2 tables without teammates"
+                ),
+            }])
+        );
+
+        // Parameters are checked along with input
+        let result = make_interval_seating(&players[..6].to_vec(), 0, WindPolicy::Fixed, 12345);
+        assert_eq!(
+            result,
+            Err(vec![
+                Diagnostic::PlayersCount { count: 6 },
+                Diagnostic::InvalidParameter {
+                    name: "step",
+                    reason: String::from("should be positive, got 0"),
+                },
+            ])
+        );
+        assert_eq!(
+            result.unwrap_err()[1].to_string(),
+            "invalid step: should be positive, got 0"
+        );
+        assert!(make_shuffled_seating(&players, &[], 0, WindPolicy::Fixed, 12345).is_err());
+        assert!(make_mcmahon_seating(&players, &[], 0, WindPolicy::Fixed, 12345).is_err());
+        assert_eq!(
+            make_balanced_seating(&players, &[], f64::NAN, -1.0, WindPolicy::Fixed, 12345)
+                .map(|result| result.seating)
+                .unwrap_err()
+                .len(),
+            2
+        );
+    }
+}
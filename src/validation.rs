@@ -0,0 +1,179 @@
+use crate::interfaces::PlayersMap;
+use std::fmt;
+
+/// Problem of seating algorithm input found by validate
+#[derive(Clone, PartialEq, Debug)]
+pub enum Diagnostic {
+    PlayersCount { count: usize }, // Players count is not a multiple of 4
+    InvalidPlayerId,               // Player id 0 is reserved for empty seats
+    DuplicatePlayer { id: u32 },   // Player id appears in players list more than once
+    MalformedTable { table: usize, size: usize }, // Previous table doesn't have exactly 4 players
+    UnknownPlayer { table: usize, id: u32 }, // Previous table has player missing in players list
+    SelfPairing { table: usize, id: u32 }, // Player is seated at previous table more than once
+    InvalidParameter { name: &'static str, reason: String }, // Parameter of seating algorithm is out of range
+}
+
+impl Diagnostic {
+    /// Index of previous table the problem is found in, None for problems of players list or parameters
+    pub fn table(&self) -> Option<usize> {
+        match self {
+            Diagnostic::PlayersCount { .. }
+            | Diagnostic::InvalidPlayerId
+            | Diagnostic::DuplicatePlayer { .. }
+            | Diagnostic::InvalidParameter { .. } => None,
+            Diagnostic::MalformedTable { table, .. }
+            | Diagnostic::UnknownPlayer { table, .. }
+            | Diagnostic::SelfPairing { table, .. } => Some(*table),
+        }
+    }
+
+    /// Whether seating algorithms can't handle the problem. Unknown players and players seated
+    /// twice at previous tables only make history less accurate, so bindings accept them unless
+    /// strict validation is requested.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(
+            self,
+            Diagnostic::UnknownPlayer { .. } | Diagnostic::SelfPairing { .. }
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::PlayersCount { count } => {
+                write!(f, "players count should be a multiple of 4, got {count}")
+            }
+            Diagnostic::InvalidPlayerId => write!(f, "player id 0 is not allowed"),
+            Diagnostic::DuplicatePlayer { id } => write!(f, "duplicate player id {id}"),
+            Diagnostic::MalformedTable { table, size } => {
+                write!(
+                    f,
+                    "previous table #{table} should have 4 players, got {size}"
+                )
+            }
+            Diagnostic::UnknownPlayer { table, id } => {
+                write!(f, "previous table #{table} has unknown player {id}")
+            }
+            Diagnostic::SelfPairing { table, id } => {
+                write!(
+                    f,
+                    "previous table #{table} has player {id} seated more than once"
+                )
+            }
+            Diagnostic::InvalidParameter { name, reason } => write!(f, "invalid {name}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Check input of seating algorithms: players ids should be unique and non-zero,
+/// players count should be a multiple of 4, and each previous table should have
+/// 4 different players from players list.
+/// Returns all problems found, empty list means input is valid.
+pub fn validate(players_map: &PlayersMap, previous_seatings: &[Vec<u32>]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if !players_map.len().is_multiple_of(4) {
+        diagnostics.push(Diagnostic::PlayersCount {
+            count: players_map.len(),
+        });
+    }
+
+    let mut ids: Vec<u32> = players_map.iter().map(|(id, _)| *id).collect();
+    ids.sort();
+    if ids.first() == Some(&0) {
+        diagnostics.push(Diagnostic::InvalidPlayerId);
+    }
+    diagnostics.extend(
+        find_duplicates(&ids)
+            .into_iter()
+            .filter(|id| *id != 0)
+            .map(|id| Diagnostic::DuplicatePlayer { id }),
+    );
+
+    for (index, table) in previous_seatings.iter().enumerate() {
+        if table.len() != 4 {
+            diagnostics.push(Diagnostic::MalformedTable {
+                table: index,
+                size: table.len(),
+            });
+        }
+
+        let mut table_ids = table.clone();
+        table_ids.sort();
+        for id in find_duplicates(&table_ids) {
+            diagnostics.push(Diagnostic::SelfPairing { table: index, id });
+        }
+        table_ids.dedup();
+        for id in table_ids {
+            if id == 0 || ids.binary_search(&id).is_err() {
+                diagnostics.push(Diagnostic::UnknownPlayer { table: index, id });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Ids appearing more than once in sorted list, each reported once
+fn find_duplicates(sorted_ids: &[u32]) -> Vec<u32> {
+    let mut duplicates: Vec<u32> = sorted_ids
+        .windows(2)
+        .filter(|pair| pair[0] == pair[1])
+        .map(|pair| pair[0])
+        .collect();
+    duplicates.dedup();
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_valid_input() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+        assert!(validate(&players, &previous_seatings).is_empty());
+        assert!(validate(&Vec::new(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_validate_diagnostics() {
+        let players: PlayersMap = vec![(1, 0), (2, 0), (3, 0), (3, 0), (0, 0), (6, 0), (6, 0)];
+        let previous_seatings = vec![
+            vec![1, 2, 3, 6],
+            vec![1, 2, 9],
+            vec![1, 1, 2, 2],
+            vec![0, 1, 2, 3],
+        ];
+
+        assert_eq!(
+            validate(&players, &previous_seatings),
+            vec![
+                Diagnostic::PlayersCount { count: 7 },
+                Diagnostic::InvalidPlayerId,
+                Diagnostic::DuplicatePlayer { id: 3 },
+                Diagnostic::DuplicatePlayer { id: 6 },
+                Diagnostic::MalformedTable { table: 1, size: 3 },
+                Diagnostic::UnknownPlayer { table: 1, id: 9 },
+                Diagnostic::SelfPairing { table: 2, id: 1 },
+                Diagnostic::SelfPairing { table: 2, id: 2 },
+                Diagnostic::UnknownPlayer { table: 3, id: 0 },
+            ]
+        );
+        assert_eq!(
+            Diagnostic::SelfPairing { table: 2, id: 1 }.to_string(),
+            "previous table #2 has player 1 seated more than once"
+        );
+        assert_eq!(
+            Diagnostic::MalformedTable { table: 1, size: 3 }.table(),
+            Some(1)
+        );
+        assert_eq!(Diagnostic::InvalidPlayerId.table(), None);
+        assert!(Diagnostic::MalformedTable { table: 1, size: 3 }.is_fatal());
+        assert!(!Diagnostic::UnknownPlayer { table: 1, id: 9 }.is_fatal());
+    }
+}
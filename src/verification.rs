@@ -0,0 +1,451 @@
+use crate::interfaces::{PlayersMap, SeatingConstraints};
+use crate::quality::make_quality_report;
+use std::fmt;
+
+/// Broken invariant of seating found by verify_seating
+#[derive(Clone, PartialEq, Debug)]
+pub enum Violation {
+    MissingPlayer { id: u32 },   // Player from players list is not seated
+    UnknownPlayer { id: u32 },   // Seated player is missing in players list
+    DuplicatePlayer { id: u32 }, // Player is seated more than once
+    IncompleteTable { table: usize, size: usize }, // Table has less than 4 players
+    ForbiddenPair { table: usize, players: (u32, u32) }, // Forbidden pair is seated at the same table
+    PinnedTable { id: u32, table: usize }, // Pinned player is not seated at their table
+    PinnedSeat { id: u32, table: usize, seat: usize }, // Pinned player is not seated at their seat
+    RepeatsMismatch { claimed: u32, actual: u32 }, // Claimed total count of repeated meetings is wrong
+    MaxRepeatsMismatch { claimed: u32, actual: u32 }, // Claimed max count of previous meetings is wrong
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MissingPlayer { id } => write!(f, "player {id} is not seated"),
+            Violation::UnknownPlayer { id } => write!(f, "unknown player {id} is seated"),
+            Violation::DuplicatePlayer { id } => write!(f, "player {id} is seated more than once"),
+            Violation::IncompleteTable { table, size } => {
+                write!(f, "table #{table} has {size} players instead of 4")
+            }
+            Violation::ForbiddenPair { table, players } => write!(
+                f,
+                "players {} and {} are seated at table #{table}",
+                players.0, players.1
+            ),
+            Violation::PinnedTable { id, table } => {
+                write!(f, "player {id} is not seated at table #{table}")
+            }
+            Violation::PinnedSeat { id, table, seat } => {
+                write!(
+                    f,
+                    "player {id} is not seated at seat {seat} of table #{table}"
+                )
+            }
+            Violation::RepeatsMismatch { claimed, actual } => {
+                write!(f, "{claimed} repeated meetings claimed, {actual} found")
+            }
+            Violation::MaxRepeatsMismatch { claimed, actual } => {
+                write!(f, "max {claimed} previous meetings claimed, {actual} found")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Result of verify_seating
+#[derive(Clone, PartialEq, Debug)]
+pub struct SeatingReport {
+    pub violations: Vec<Violation>, // Empty if seating is correct
+    pub repeats: u32, // Total count of previous meetings of players seated at the same tables
+    pub max_repeats: u32, // Max count of previous meetings of two players seated at the same table
+}
+
+/// Check seating produced by seating algorithm against its input: every player of $players_map
+/// is seated exactly once, all tables have 4 players, no forbidden pair is seated together,
+/// pinned players are seated at their tables and seats, and repeats claimed by the algorithm
+/// match repeats of quality report (see make_quality_report).
+/// $previous_seatings should be tables of 4 players, as checked by validate.
+pub fn verify_seating(
+    players_map: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    seating: &PlayersMap,
+    constraints: &SeatingConstraints,
+) -> SeatingReport {
+    let mut violations = Vec::new();
+
+    let mut expected: Vec<u32> = players_map.iter().map(|(id, _)| *id).collect();
+    expected.sort();
+    expected.dedup();
+    let mut seated: Vec<u32> = seating.iter().map(|(id, _)| *id).collect();
+    seated.sort();
+
+    for id in &expected {
+        if seated.binary_search(id).is_err() {
+            violations.push(Violation::MissingPlayer { id: *id });
+        }
+    }
+    let mut previous = None;
+    for id in &seated {
+        if previous == Some(*id) {
+            if violations.last() != Some(&Violation::DuplicatePlayer { id: *id }) {
+                violations.push(Violation::DuplicatePlayer { id: *id });
+            }
+        } else if expected.binary_search(id).is_err() {
+            violations.push(Violation::UnknownPlayer { id: *id });
+        }
+        previous = Some(*id);
+    }
+
+    for (index, table) in seating.chunks(4).enumerate() {
+        if table.len() != 4 {
+            violations.push(Violation::IncompleteTable {
+                table: index,
+                size: table.len(),
+            });
+        }
+
+        for i in 0..table.len() {
+            for j in i + 1..table.len() {
+                let (player1, player2) = (table[i].0, table[j].0);
+                let forbidden = constraints
+                    .forbidden
+                    .iter()
+                    .any(|pair| *pair == (player1, player2) || *pair == (player2, player1));
+                if forbidden {
+                    violations.push(Violation::ForbiddenPair {
+                        table: index,
+                        players: (player1, player2),
+                    });
+                }
+            }
+        }
+    }
+
+    for pinned in &constraints.pinned {
+        let (id, table) = (pinned.id, pinned.table);
+        match pinned.seat {
+            Some(seat) => {
+                if seat >= 4 || seating.get(4 * table + seat).map(|(id, _)| *id) != Some(id) {
+                    violations.push(Violation::PinnedSeat { id, table, seat });
+                }
+            }
+            None => {
+                let is_seated = seating
+                    .chunks(4)
+                    .nth(table)
+                    .is_some_and(|players| players.iter().any(|player| player.0 == id));
+                if !is_seated {
+                    violations.push(Violation::PinnedTable { id, table });
+                }
+            }
+        }
+    }
+
+    let quality = make_quality_report(seating, previous_seatings);
+    let (repeats, max_repeats) = (quality.repeats, quality.max_repeats);
+    if let Some(claimed) = constraints.claimed_repeats {
+        if claimed != repeats {
+            violations.push(Violation::RepeatsMismatch {
+                claimed,
+                actual: repeats,
+            });
+        }
+    }
+    if let Some(claimed) = constraints.claimed_max_repeats {
+        if claimed != max_repeats {
+            violations.push(Violation::MaxRepeatsMismatch {
+                claimed,
+                actual: max_repeats,
+            });
+        }
+    }
+
+    SeatingReport {
+        violations,
+        repeats,
+        max_repeats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::{PinnedSeat, WindPolicy};
+    use crate::seating_interval::make_interval_seating;
+    use crate::seating_shuffle::make_shuffled_seating;
+    use crate::seating_swiss::make_swiss_seating_with_repeats;
+    use crate::seating_swiss_exact::make_exact_swiss_seating;
+    use crate::seating_team::make_team_seating;
+    use crate::shuffle::shuffle;
+    use lcg_rand::rand::LCG;
+
+    #[test]
+    fn test_verify_seating() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let previous_seatings = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![1, 2, 5, 6]];
+        let seating: PlayersMap = [1, 2, 7, 8, 3, 4, 5, 6]
+            .iter()
+            .map(|id| (*id, 1500))
+            .collect();
+
+        let report = verify_seating(
+            &players,
+            &previous_seatings,
+            &seating,
+            &SeatingConstraints {
+                forbidden: vec![(8, 1)],
+                pinned: vec![
+                    PinnedSeat {
+                        id: 7,
+                        table: 0,
+                        seat: None,
+                    },
+                    PinnedSeat {
+                        id: 5,
+                        table: 1,
+                        seat: Some(2),
+                    },
+                ],
+                claimed_repeats: Some(6),
+                claimed_max_repeats: Some(2),
+            },
+        );
+        assert_eq!(
+            report,
+            SeatingReport {
+                violations: vec![Violation::ForbiddenPair {
+                    table: 0,
+                    players: (1, 8)
+                }],
+                repeats: 6,
+                max_repeats: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_seating_violations() {
+        let players: PlayersMap = (1..=8).map(|id| (id, 1500)).collect();
+        let seating: PlayersMap = [1, 2, 3, 3, 9, 4, 5].iter().map(|id| (*id, 1500)).collect();
+
+        let report = verify_seating(
+            &players,
+            &[vec![1, 2, 3, 4]],
+            &seating,
+            &SeatingConstraints {
+                pinned: vec![
+                    PinnedSeat {
+                        id: 1,
+                        table: 1,
+                        seat: None,
+                    },
+                    PinnedSeat {
+                        id: 4,
+                        table: 1,
+                        seat: Some(3),
+                    },
+                    PinnedSeat {
+                        id: 2,
+                        table: 5,
+                        seat: Some(0),
+                    },
+                ],
+                claimed_repeats: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            report.violations,
+            vec![
+                Violation::MissingPlayer { id: 6 },
+                Violation::MissingPlayer { id: 7 },
+                Violation::MissingPlayer { id: 8 },
+                Violation::DuplicatePlayer { id: 3 },
+                Violation::UnknownPlayer { id: 9 },
+                Violation::IncompleteTable { table: 1, size: 3 },
+                Violation::PinnedTable { id: 1, table: 1 },
+                Violation::PinnedSeat {
+                    id: 4,
+                    table: 1,
+                    seat: 3
+                },
+                Violation::PinnedSeat {
+                    id: 2,
+                    table: 5,
+                    seat: 0
+                },
+                // Duplicated player 3 is not counted as meeting themselves
+                Violation::RepeatsMismatch {
+                    claimed: 2,
+                    actual: 5
+                },
+            ]
+        );
+        assert_eq!(
+            report.violations[4].to_string(),
+            "unknown player 9 is seated"
+        );
+        assert_eq!(
+            report.violations[7].to_string(),
+            "player 4 is not seated at seat 3 of table #1"
+        );
+    }
+
+    /// Random players list and history of several sessions for given seed
+    fn make_tournament(seed: u64) -> (PlayersMap, Vec<Vec<u32>>) {
+        let mut random = LCG::from_seed(seed);
+        let players_count = 4 * (2 + random.next() as usize % 10);
+        let sessions_count = random.next() as usize % 6;
+        let players: PlayersMap = (1..=players_count as u32)
+            .map(|id| (id * 3 + 7, 1000 + (random.next() % 1000) as i32))
+            .collect();
+
+        let mut previous_seatings = Vec::new();
+        for _ in 0..sessions_count {
+            for table in shuffle(&players, &mut random).chunks(4) {
+                previous_seatings.push(table.iter().map(|(id, _)| *id).collect());
+            }
+        }
+        (players, previous_seatings)
+    }
+
+    #[test]
+    fn test_seating_invariants_over_seeds() {
+        for seed in 0..40 {
+            let (players, previous_seatings) = make_tournament(seed);
+            let check = |seating: &PlayersMap, constraints: &SeatingConstraints| {
+                let report = verify_seating(&players, &previous_seatings, seating, constraints);
+                assert!(
+                    report.violations.is_empty(),
+                    "seed {seed}: {:?}",
+                    report.violations
+                );
+                report
+            };
+
+            let swiss = make_swiss_seating_with_repeats(
+                &players,
+                &previous_seatings,
+                WindPolicy::Random,
+                seed,
+            );
+            check(
+                &swiss.seating,
+                &SeatingConstraints {
+                    claimed_repeats: Some(swiss.repeats),
+                    claimed_max_repeats: Some(swiss.max_repeats),
+                    ..Default::default()
+                },
+            );
+
+            // Players are shuffled only inside their groups of consecutive players
+            let groups_count = 1 + seed as usize % 3;
+            let shuffled = make_shuffled_seating(
+                &players,
+                &previous_seatings,
+                groups_count as u32,
+                WindPolicy::BalancedByHistory,
+                seed,
+            );
+            check(&shuffled, &SeatingConstraints::default());
+            let group_size = players.len().div_ceil(groups_count);
+            let group_of =
+                |id: u32| players.iter().position(|player| player.0 == id).unwrap() / group_size;
+            for (index, table) in shuffled.chunks(4).enumerate() {
+                let mut table_groups: Vec<usize> =
+                    table.iter().map(|(id, _)| group_of(*id)).collect();
+                table_groups.sort();
+                let expected: Vec<usize> = (4 * index..4 * index + 4)
+                    .map(|place| place / group_size)
+                    .collect();
+                assert_eq!(table_groups, expected, "seed {seed}");
+            }
+
+            // Tables are made of players $step places apart, extra tables at the bottom with step 1
+            let step = 1 + seed as usize % 4;
+            let interval = make_interval_seating(&players, step, WindPolicy::Fixed, seed);
+            check(&interval, &SeatingConstraints::default());
+            let remainder_start = players.len() - 4 * (players.len() / 4 % step);
+            let mut expected_tables: Vec<Vec<u32>> = Vec::new();
+            for offset in 0..step {
+                let group: Vec<u32> = players[..remainder_start]
+                    .iter()
+                    .skip(offset)
+                    .step_by(step)
+                    .map(|(id, _)| *id)
+                    .collect();
+                expected_tables.extend(group.chunks(4).map(|table| table.to_vec()));
+            }
+            expected_tables.extend(
+                players[remainder_start..]
+                    .chunks(4)
+                    .map(|table| table.iter().map(|(id, _)| *id).collect()),
+            );
+            let mut tables: Vec<Vec<u32>> = interval
+                .chunks(4)
+                .map(|table| table.iter().map(|(id, _)| *id).collect())
+                .collect();
+            for table in expected_tables.iter_mut().chain(tables.iter_mut()) {
+                table.sort();
+            }
+            expected_tables.sort();
+            tables.sort();
+            assert_eq!(tables, expected_tables, "seed {seed}");
+
+            let exact = make_exact_swiss_seating(
+                &players,
+                &previous_seatings,
+                500,
+                WindPolicy::Fixed,
+                seed,
+            );
+            let report = check(
+                &exact.seating,
+                &SeatingConstraints {
+                    claimed_repeats: Some(exact.repeats),
+                    ..Default::default()
+                },
+            );
+            assert!(report.repeats >= exact.lower_bound, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_team_seating_honors_forbidden_pairs() {
+        for seed in 0..20 {
+            let (players, previous_seatings) = make_tournament(seed);
+            // Teams of 2 players: teammates must not be seated together
+            let teams: Vec<(u32, u32)> = players
+                .iter()
+                .enumerate()
+                .map(|(index, (id, _))| (*id, index as u32 / 2))
+                .collect();
+            let forbidden: Vec<(u32, u32)> = players
+                .chunks(2)
+                .map(|pair| (pair[0].0, pair[1].0))
+                .collect();
+
+            let seating = make_team_seating(
+                &players,
+                &teams,
+                &previous_seatings,
+                WindPolicy::Fixed,
+                seed,
+            )
+            .unwrap();
+            let report = verify_seating(
+                &players,
+                &previous_seatings,
+                &seating,
+                &SeatingConstraints {
+                    forbidden,
+                    ..Default::default()
+                },
+            );
+            assert!(
+                report.violations.is_empty(),
+                "seed {seed}: {:?}",
+                report.violations
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::interfaces::{PlayersMap, WindPolicy};
+use crate::intersections::make_intersections_table;
+use crate::seating_interval::make_interval_seating;
+use crate::seating_shuffle::make_shuffled_seating;
+use crate::seating_swiss::make_swiss_seating;
+use crate::seating_team::make_team_seating;
+use crate::validation::validate;
+use wasm_bindgen::prelude::*;
+
+/// Rule of assigning winds at the table, see WindPolicy
+#[wasm_bindgen(js_name = WindPolicy)]
+#[derive(Clone, Copy)]
+pub enum JsWindPolicy {
+    Random,
+    BalancedByHistory,
+    ByRating,
+    Fixed,
+}
+
+impl From<JsWindPolicy> for WindPolicy {
+    fn from(policy: JsWindPolicy) -> WindPolicy {
+        match policy {
+            JsWindPolicy::Random => WindPolicy::Random,
+            JsWindPolicy::BalancedByHistory => WindPolicy::BalancedByHistory,
+            JsWindPolicy::ByRating => WindPolicy::ByRating,
+            JsWindPolicy::Fixed => WindPolicy::Fixed,
+        }
+    }
+}
+
+/// Seating result: ids and ratings of players, 4 players per table
+#[wasm_bindgen(js_name = Seating)]
+pub struct JsSeating {
+    ids: Vec<u32>,
+    ratings: Vec<i32>,
+}
+
+#[wasm_bindgen(js_class = Seating)]
+impl JsSeating {
+    /// Ids of players as Uint32Array
+    pub fn ids(&self) -> Vec<u32> {
+        self.ids.clone()
+    }
+
+    /// Ratings of players as Int32Array
+    pub fn ratings(&self) -> Vec<i32> {
+        self.ratings.clone()
+    }
+}
+
+impl From<PlayersMap> for JsSeating {
+    fn from(seating: PlayersMap) -> JsSeating {
+        JsSeating {
+            ids: seating.iter().map(|item| item.0).collect(),
+            ratings: seating.iter().map(|item| item.1).collect(),
+        }
+    }
+}
+
+/// Zip ids and ratings into players map
+fn make_players_map(ids: &[u32], ratings: &[i32]) -> Result<PlayersMap, JsError> {
+    if ids.len() != ratings.len() {
+        return Err(JsError::new("ids and ratings should have the same length"));
+    }
+    Ok(ids.iter().copied().zip(ratings.iter().copied()).collect())
+}
+
+/// Split flat list of previous seatings into tables of 4 players
+fn make_previous_seatings(previous_seatings: &[u32]) -> Result<Vec<Vec<u32>>, JsError> {
+    if !previous_seatings.len().is_multiple_of(4) {
+        return Err(JsError::new(
+            "previous seatings should contain 4 players per table",
+        ));
+    }
+    Ok(previous_seatings
+        .chunks(4)
+        .map(|table| table.to_vec())
+        .collect())
+}
+
+/// Check players and previous seatings, problems seating can't handle are reported in one error.
+/// Unknown and repeated players in previous seatings are accepted, see js_validate_input.
+fn check_input(players: &PlayersMap, previous_seatings: &[Vec<u32>]) -> Result<(), JsError> {
+    let messages: Vec<String> = validate(players, previous_seatings)
+        .iter()
+        .filter(|diagnostic| diagnostic.is_fatal())
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    if messages.is_empty() {
+        return Ok(());
+    }
+    Err(JsError::new(&messages.join("; ")))
+}
+
+/// Strict validation of players and previous seatings, see validate.
+/// Returns messages of all problems found, empty array if input is valid.
+#[wasm_bindgen(js_name = validateInput)]
+pub fn js_validate_input(
+    ids: &[u32],
+    ratings: &[i32],
+    previous_seatings: &[u32],
+) -> Result<Vec<String>, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    let previous_seatings = make_previous_seatings(previous_seatings)?;
+    Ok(validate(&players, &previous_seatings)
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect())
+}
+
+/// Swiss seating, see make_swiss_seating.
+/// $previous_seatings is a flat list of players ids, 4 per table.
+#[wasm_bindgen(js_name = makeSwissSeating)]
+pub fn js_make_swiss_seating(
+    ids: &[u32],
+    ratings: &[i32],
+    previous_seatings: &[u32],
+    wind_policy: JsWindPolicy,
+    rand_factor: u64,
+) -> Result<JsSeating, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    let previous_seatings = make_previous_seatings(previous_seatings)?;
+    check_input(&players, &previous_seatings)?;
+    Ok(make_swiss_seating(
+        &players,
+        &previous_seatings,
+        wind_policy.into(),
+        rand_factor,
+    )
+    .into())
+}
+
+/// Shuffled seating, see make_shuffled_seating.
+/// $previous_seatings is a flat list of players ids, 4 per table.
+#[wasm_bindgen(js_name = makeShuffledSeating)]
+pub fn js_make_shuffled_seating(
+    ids: &[u32],
+    ratings: &[i32],
+    previous_seatings: &[u32],
+    groups_count: u32,
+    wind_policy: JsWindPolicy,
+    rand_factor: u64,
+) -> Result<JsSeating, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    let previous_seatings = make_previous_seatings(previous_seatings)?;
+    check_input(&players, &previous_seatings)?;
+    Ok(make_shuffled_seating(
+        &players,
+        &previous_seatings,
+        groups_count.max(1),
+        wind_policy.into(),
+        rand_factor,
+    )
+    .into())
+}
+
+/// Team seating, see make_team_seating.
+/// $teams is a flat list of (player id, team id) pairs, $previous_seatings is a flat list
+/// of players ids, 4 per table.
+#[wasm_bindgen(js_name = makeTeamSeating)]
+pub fn js_make_team_seating(
+    ids: &[u32],
+    ratings: &[i32],
+    teams: &[u32],
+    previous_seatings: &[u32],
+    wind_policy: JsWindPolicy,
+    rand_factor: u64,
+) -> Result<JsSeating, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    let previous_seatings = make_previous_seatings(previous_seatings)?;
+    check_input(&players, &previous_seatings)?;
+    if !teams.len().is_multiple_of(2) {
+        return Err(JsError::new(
+            "teams should contain (player id, team id) pairs",
+        ));
+    }
+    let teams: Vec<(u32, u32)> = teams.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+    make_team_seating(
+        &players,
+        &teams,
+        &previous_seatings,
+        wind_policy.into(),
+        rand_factor,
+    )
+    .map(|seating| seating.into())
+    .map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// Interval seating, see make_interval_seating
+#[wasm_bindgen(js_name = makeIntervalSeating)]
+pub fn js_make_interval_seating(
+    ids: &[u32],
+    ratings: &[i32],
+    step: usize,
+    wind_policy: JsWindPolicy,
+    rand_factor: u64,
+) -> Result<JsSeating, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    check_input(&players, &[])?;
+    if step == 0 {
+        return Err(JsError::new("step should be positive"));
+    }
+    Ok(make_interval_seating(&players, step, wind_policy.into(), rand_factor).into())
+}
+
+/// Intersections table, see make_intersections_table.
+/// Returns flat Uint32Array of (player1, player2, games played together) triples.
+#[wasm_bindgen(js_name = makeIntersectionsTable)]
+pub fn js_make_intersections_table(
+    ids: &[u32],
+    ratings: &[i32],
+    previous_seatings: &[u32],
+) -> Result<Vec<u32>, JsError> {
+    let players = make_players_map(ids, ratings)?;
+    let previous_seatings = make_previous_seatings(previous_seatings)?;
+    check_input(&players, &previous_seatings)?;
+    Ok(make_intersections_table(&players, &previous_seatings)
+        .iter()
+        .flat_map(|item| [item.0, item.1, item.2])
+        .collect())
+}
@@ -0,0 +1,484 @@
+use crate::interfaces::{PlayersMap, SeatingWithDealers, WindPolicy, WindsBalance};
+use crate::minimap::Minimap;
+use crate::shuffle::update_places_to_random;
+use lcg_rand::rand::LCG;
+
+const POSSIBLE_PLACEMENTS: [(u8, u8, u8, u8); 24] = [
+    (0u8, 1u8, 2u8, 3u8),
+    (1u8, 0u8, 2u8, 3u8),
+    (2u8, 0u8, 1u8, 3u8),
+    (3u8, 0u8, 1u8, 2u8),
+    (0u8, 1u8, 3u8, 2u8),
+    (1u8, 0u8, 3u8, 2u8),
+    (2u8, 0u8, 3u8, 1u8),
+    (3u8, 0u8, 2u8, 1u8),
+    (0u8, 2u8, 1u8, 3u8),
+    (1u8, 2u8, 0u8, 3u8),
+    (2u8, 1u8, 0u8, 3u8),
+    (3u8, 1u8, 0u8, 2u8),
+    (0u8, 2u8, 3u8, 1u8),
+    (1u8, 2u8, 3u8, 0u8),
+    (2u8, 1u8, 3u8, 0u8),
+    (3u8, 1u8, 2u8, 0u8),
+    (0u8, 3u8, 1u8, 2u8),
+    (1u8, 3u8, 0u8, 2u8),
+    (2u8, 3u8, 0u8, 1u8),
+    (3u8, 2u8, 0u8, 1u8),
+    (0u8, 3u8, 2u8, 1u8),
+    (1u8, 3u8, 2u8, 0u8),
+    (2u8, 3u8, 1u8, 0u8),
+    (3u8, 2u8, 1u8, 0u8),
+];
+
+/// Assign winds at each table according to the policy.
+/// Seating is expected to be split into tables of 4 players; order at the table is E-S-W-N.
+pub(crate) fn apply_wind_policy(
+    seating: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+    wind_policy: WindPolicy,
+    rand_factor: u64,
+) -> PlayersMap {
+    match wind_policy {
+        WindPolicy::Random => update_places_to_random(seating, rand_factor),
+        WindPolicy::BalancedByHistory => update_places_at_each_table(seating, previous_seatings),
+        WindPolicy::ByRating => update_places_by_rating(seating),
+        WindPolicy::Fixed => seating.clone(),
+    }
+}
+
+/// Player with highest rating at the table sits East, lowest sits North
+fn update_places_by_rating(seating: &PlayersMap) -> PlayersMap {
+    let mut result_seating = Vec::new();
+    for chunk in seating.chunks(4) {
+        let mut table = chunk.to_vec();
+        table.sort_by_key(|item| std::cmp::Reverse(item.1));
+        result_seating.extend(table);
+    }
+
+    result_seating
+}
+
+/// Make sure players will initially sit to winds that they did not seat before
+/// (or sat less times)
+pub(crate) fn update_places_at_each_table(
+    seating: &PlayersMap,
+    previous_seatings: &[Vec<u32>],
+) -> PlayersMap {
+    let mut tables = Vec::new();
+    for chunk in seating.chunks(4) {
+        tables.push(chunk.to_vec());
+    }
+
+    let mut result_seating = Vec::new();
+    for table in tables {
+        let mut best_result = 10005000;
+        let mut best_placement = Vec::new();
+
+        for placement in &POSSIBLE_PLACEMENTS {
+            let new_result = calc_sub_sums(
+                table[placement.0 as usize].0,
+                table[placement.1 as usize].0,
+                table[placement.2 as usize].0,
+                table[placement.3 as usize].0,
+                previous_seatings,
+            );
+
+            if new_result < best_result {
+                best_result = new_result;
+                best_placement = vec![
+                    table[placement.0 as usize],
+                    table[placement.1 as usize],
+                    table[placement.2 as usize],
+                    table[placement.3 as usize],
+                ];
+            }
+        }
+
+        result_seating.extend(best_placement);
+    }
+
+    result_seating
+}
+
+/// Calculate index of distribution equality for seating at particular
+/// winds. Ideally, we want that seating, which produces smallest index.
+fn calc_sub_sums(
+    player1: u32,
+    player2: u32,
+    player3: u32,
+    player4: u32,
+    prev_data: &[Vec<u32>],
+) -> u32 {
+    let mut total_sum = 0;
+
+    for (idx, &player) in [player1, player2, player3, player4].iter().enumerate() {
+        let mut buckets = [0u32, 0u32, 0u32, 0u32];
+        buckets[idx] += 1;
+
+        // Tables of other size don't define winds, e.g. malformed rows of history
+        for table in prev_data.iter().filter(|table| table.len() == 4) {
+            if let Some(idx_at_table) = table.iter().position(|&p| p == player) {
+                buckets[idx_at_table] += 1;
+            }
+        }
+
+        total_sum += buckets[0].abs_diff(buckets[1])
+            + buckets[0].abs_diff(buckets[2])
+            + buckets[0].abs_diff(buckets[3])
+            + buckets[1].abs_diff(buckets[2])
+            + buckets[1].abs_diff(buckets[3])
+            + buckets[2].abs_diff(buckets[3]);
+    }
+
+    total_sum
+}
+
+/// Tournament-wide winds balancing
+/// Assigns winds at every table of pre-generated schedule, so that counts of every wind
+/// for each player differ by at most one. Optimization is heuristic: players whose counts still
+/// differ by more than one are reported as still unbalanced, which doesn't mean that balance
+/// is impossible for them.
+/// Tables which don't have 4 players are left as is and their winds are not counted.
+pub fn balance_winds(sessions: &[Vec<Vec<u32>>]) -> WindsBalance {
+    const MAX_PASSES: usize = 20;
+
+    let mut ids: Vec<u32> = sessions.iter().flatten().flatten().copied().collect();
+    ids.sort();
+    ids.dedup();
+
+    let mut wind_counts: Minimap<[u32; 4]> = Minimap::new(ids.len());
+    ids.iter().for_each(|id| wind_counts.set_value(*id, [0; 4]));
+
+    // Greedy placement session by session
+    let mut result_sessions = sessions.to_vec();
+    for table in result_sessions
+        .iter_mut()
+        .flatten()
+        .filter(|table| table.len() == 4)
+    {
+        *table = find_best_placement(table, &wind_counts);
+        update_wind_counts(table, &mut wind_counts, true);
+    }
+
+    // Then re-place each table knowing winds of all other tables in the tournament
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for table in result_sessions
+            .iter_mut()
+            .flatten()
+            .filter(|table| table.len() == 4)
+        {
+            update_wind_counts(table, &mut wind_counts, false);
+            let best_placement = find_best_placement(table, &wind_counts);
+            if calc_placement_cost(&best_placement, &wind_counts)
+                < calc_placement_cost(table, &wind_counts)
+            {
+                *table = best_placement;
+                improved = true;
+            }
+            update_wind_counts(table, &mut wind_counts, true);
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    WindsBalance {
+        sessions: result_sessions,
+        still_unbalanced_players: find_unbalanced_players(&ids, &wind_counts),
+    }
+}
+
+/// Find placement at the table where players sit to winds they sat less times.
+/// First of equally good placements is taken.
+fn find_best_placement(table: &[u32], wind_counts: &Minimap<[u32; 4]>) -> Vec<u32> {
+    let mut best_placement = table.to_vec();
+    let mut best_cost = calc_placement_cost(table, wind_counts);
+
+    for placement in &POSSIBLE_PLACEMENTS {
+        let new_placement = vec![
+            table[placement.0 as usize],
+            table[placement.1 as usize],
+            table[placement.2 as usize],
+            table[placement.3 as usize],
+        ];
+        let new_cost = calc_placement_cost(&new_placement, wind_counts);
+        if new_cost < best_cost {
+            best_cost = new_cost;
+            best_placement = new_placement;
+        }
+    }
+
+    best_placement
+}
+
+/// Sum of counts of winds players are going to take.
+/// Minimizing it also minimizes sum of squares of wind counts, which is lowest for even distribution.
+fn calc_placement_cost(table: &[u32], wind_counts: &Minimap<[u32; 4]>) -> u32 {
+    table
+        .iter()
+        .enumerate()
+        .map(|(wind, id)| wind_counts.get_value(*id).unwrap()[wind])
+        .sum()
+}
+
+fn update_wind_counts(table: &[u32], wind_counts: &mut Minimap<[u32; 4]>, add: bool) {
+    for (wind, id) in table.iter().enumerate() {
+        let mut counts = wind_counts.get_value(*id).unwrap();
+        if add {
+            counts[wind] += 1;
+        } else {
+            counts[wind] -= 1;
+        }
+        wind_counts.set_value(*id, counts);
+    }
+}
+
+/// Players whose counts of winds differ by more than one
+fn find_unbalanced_players(ids: &[u32], wind_counts: &Minimap<[u32; 4]>) -> Vec<u32> {
+    ids.iter()
+        .filter(|id| {
+            let counts = wind_counts.get_value(**id).unwrap();
+            counts.iter().max().unwrap() - counts.iter().min().unwrap() > 1
+        })
+        .copied()
+        .collect()
+}
+
+/// Choose player who deals first at each table, independently of seating order.
+/// Dealer is drawn with seeded RNG among players of the table who started as dealer
+/// least times, according to list of first dealers of previous sessions.
+pub fn choose_first_dealers(
+    seating: &PlayersMap,
+    previous_dealers: &[u32],
+    rand_factor: u64,
+) -> SeatingWithDealers {
+    let mut random: LCG = LCG::from_seed(rand_factor);
+    let mut first_dealers = Vec::new();
+
+    for table in seating.chunks(4) {
+        let dealer_counts: Vec<usize> = table
+            .iter()
+            .map(|(id, _)| {
+                previous_dealers
+                    .iter()
+                    .filter(|dealer| *dealer == id)
+                    .count()
+            })
+            .collect();
+        let min_count = dealer_counts.iter().min().copied().unwrap_or(0);
+        let candidates: Vec<u32> = table
+            .iter()
+            .zip(dealer_counts.iter())
+            .filter(|(_, count)| **count == min_count)
+            .map(|((id, _), _)| *id)
+            .collect();
+
+        first_dealers.push(candidates[random.next() as usize % candidates.len()]);
+    }
+
+    SeatingWithDealers {
+        seating: seating.clone(),
+        first_dealers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_sub_sums() {
+        let prev_seating: Vec<Vec<u32>> =
+            vec![vec![1, 2, 3, 4], vec![4, 1, 2, 3], vec![3, 4, 1, 2]];
+
+        assert_eq!(calc_sub_sums(1, 2, 3, 4, &prev_seating), 24);
+    }
+
+    #[test]
+    fn test_update_places_at_each_table() {
+        let players = vec![(1, 1500), (2, 1500), (3, 1500), (4, 1500)];
+
+        let previous_seating: Vec<Vec<u32>> =
+            vec![vec![1, 2, 3, 4], vec![4, 1, 2, 3], vec![3, 4, 1, 2]];
+
+        assert_eq!(
+            update_places_at_each_table(&players, &previous_seating),
+            vec![(2, 1500), (3, 1500), (4, 1500), (1, 1500)]
+        )
+    }
+
+    #[test]
+    fn test_apply_wind_policy() {
+        let seating = vec![
+            (1, 1500),
+            (2, 1700),
+            (3, 1600),
+            (4, 1800),
+            (5, 1200),
+            (6, 1100),
+            (7, 1400),
+            (8, 1300),
+        ];
+
+        assert_eq!(
+            apply_wind_policy(&seating, &[], WindPolicy::Fixed, 12345),
+            seating
+        );
+        assert_eq!(
+            apply_wind_policy(&seating, &[], WindPolicy::ByRating, 12345),
+            vec![
+                (4, 1800),
+                (2, 1700),
+                (3, 1600),
+                (1, 1500),
+                (7, 1400),
+                (8, 1300),
+                (5, 1200),
+                (6, 1100),
+            ]
+        );
+        assert_eq!(
+            apply_wind_policy(&seating, &[], WindPolicy::Random, 12345),
+            update_places_to_random(&seating, 12345)
+        );
+    }
+
+    fn make_sessions() -> Vec<Vec<Vec<u32>>> {
+        vec![
+            vec![
+                vec![1, 2, 3, 4],
+                vec![5, 6, 7, 8],
+                vec![9, 10, 11, 12],
+                vec![13, 14, 15, 16],
+            ],
+            vec![
+                vec![1, 5, 9, 13],
+                vec![2, 6, 10, 14],
+                vec![3, 7, 11, 15],
+                vec![4, 8, 12, 16],
+            ],
+            vec![
+                vec![1, 6, 11, 16],
+                vec![2, 5, 12, 15],
+                vec![3, 8, 9, 14],
+                vec![4, 7, 10, 13],
+            ],
+            vec![
+                vec![1, 7, 12, 14],
+                vec![2, 8, 11, 13],
+                vec![3, 5, 10, 16],
+                vec![4, 6, 9, 15],
+            ],
+            vec![
+                vec![1, 8, 10, 15],
+                vec![2, 7, 9, 16],
+                vec![3, 6, 12, 13],
+                vec![4, 5, 11, 14],
+            ],
+            vec![
+                vec![1, 2, 3, 4],
+                vec![5, 6, 7, 8],
+                vec![9, 10, 11, 12],
+                vec![13, 14, 15, 16],
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_balance_winds() {
+        let sessions = make_sessions();
+        let result = balance_winds(&sessions);
+        assert!(result.still_unbalanced_players.is_empty());
+
+        // Same players at the same tables, only winds are changed
+        for (session, balanced_session) in sessions.iter().zip(result.sessions.iter()) {
+            for (table, balanced_table) in session.iter().zip(balanced_session.iter()) {
+                let mut ids = balanced_table.clone();
+                ids.sort();
+                assert_eq!(&ids, table);
+            }
+        }
+
+        // 6 sessions: every player sits to each wind once or twice
+        for id in 1..=16 {
+            let mut counts = [0; 4];
+            for table in result.sessions.iter().flatten() {
+                if let Some(wind) = table.iter().position(|player| *player == id) {
+                    counts[wind] += 1;
+                }
+            }
+            assert!(counts.iter().all(|count| *count == 1 || *count == 2));
+        }
+    }
+
+    #[test]
+    fn test_balance_winds_skips_malformed_tables() {
+        let sessions = vec![
+            vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8]],
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]],
+        ];
+        let result = balance_winds(&sessions);
+
+        assert_eq!(result.sessions[0], sessions[0]);
+        assert!(result.still_unbalanced_players.is_empty());
+
+        // Rows of history which aren't 4 players don't affect winds
+        let seating: PlayersMap = (1..=4).map(|id| (id, 1500)).collect();
+        assert_eq!(
+            update_places_at_each_table(&seating, &[vec![5, 6, 7, 8, 1], vec![2, 3]]),
+            update_places_at_each_table(&seating, &[])
+        );
+    }
+
+    #[test]
+    fn test_find_unbalanced_players() {
+        let ids = vec![1, 2, 3];
+        let mut wind_counts = Minimap::new(ids.len());
+        wind_counts.fill_with(&[(1, [2, 1, 1, 2]), (2, [3, 1, 1, 1]), (3, [0, 0, 1, 0])]);
+        assert_eq!(find_unbalanced_players(&ids, &wind_counts), vec![2]);
+    }
+
+    #[test]
+    fn test_choose_first_dealers() {
+        let seating = vec![
+            (1, 1500),
+            (2, 1500),
+            (3, 1500),
+            (4, 1500),
+            (5, 1500),
+            (6, 1500),
+            (7, 1500),
+            (8, 1500),
+        ];
+
+        let result = choose_first_dealers(&seating, &[1, 2, 3, 1], 12345);
+        assert_eq!(result.seating, seating);
+        assert_eq!(result.first_dealers.len(), 2);
+        assert_eq!(result.first_dealers[0], 4);
+        assert!((5..=8).contains(&result.first_dealers[1]));
+    }
+
+    #[test]
+    fn test_choose_first_dealers_over_tournament() {
+        let seating = vec![(1, 1500), (2, 1500), (3, 1500), (4, 1500)];
+        let mut previous_dealers = Vec::new();
+
+        for session in 0..8 {
+            let result = choose_first_dealers(&seating, &previous_dealers, 12345 + session);
+            previous_dealers.extend(result.first_dealers);
+        }
+
+        for id in 1..=4 {
+            assert_eq!(
+                previous_dealers
+                    .iter()
+                    .filter(|dealer| **dealer == id)
+                    .count(),
+                2
+            );
+        }
+    }
+}
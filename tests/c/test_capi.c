@@ -0,0 +1,132 @@
+/* C test harness for capi feature. Run with: make -C tests/c */
+#include <assert.h>
+#include <stdio.h>
+
+#include "mahjong_seatings.h"
+
+static void test_swiss_seating(void) {
+    uint32_t ids[16];
+    int32_t ratings[16];
+    for (uint32_t i = 0; i < 16; i++) {
+        ids[i] = i + 1;
+        ratings[i] = 1500 - (int32_t)i;
+    }
+    uint32_t previous[16] = {1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16};
+
+    MsSeating seating;
+    int32_t code = ms_make_swiss_seating(ids, ratings, 16, previous, 16, MS_WIND_POLICY_FIXED, 12345,
+                                         &seating);
+    assert(code == MS_OK);
+    assert(seating.len == 16);
+
+    uint32_t sum = 0;
+    for (size_t i = 0; i < seating.len; i++) {
+        sum += seating.ids[i];
+    }
+    assert(sum == 136);
+
+    ms_seating_free(&seating);
+    assert(seating.ids == NULL && seating.ratings == NULL);
+    ms_seating_free(&seating);
+}
+
+static void test_shuffled_and_interval_seating(void) {
+    uint32_t ids[8] = {1, 2, 3, 4, 5, 6, 7, 8};
+    int32_t ratings[8] = {8, 7, 6, 5, 4, 3, 2, 1};
+
+    MsSeating seating;
+    assert(ms_make_shuffled_seating(ids, ratings, 8, NULL, 0, 1, MS_WIND_POLICY_RANDOM, 1, &seating) ==
+           MS_OK);
+    assert(seating.len == 8);
+    ms_seating_free(&seating);
+
+    assert(ms_make_interval_seating(ids, ratings, 8, 2, MS_WIND_POLICY_FIXED, 1, &seating) == MS_OK);
+    assert(seating.len == 8);
+    ms_seating_free(&seating);
+}
+
+static void test_team_seating(void) {
+    uint32_t ids[8] = {1, 2, 3, 4, 5, 6, 7, 8};
+    int32_t ratings[8] = {8, 7, 6, 5, 4, 3, 2, 1};
+    uint32_t teams[6] = {1, 10, 2, 10, 3, 10};
+
+    MsSeating seating;
+    assert(ms_make_team_seating(ids, ratings, 8, teams, 4, NULL, 0, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_OK);
+    assert(seating.len == 8);
+    ms_seating_free(&seating);
+
+    /* Team 10 of 3 players can't be seated at 2 tables */
+    assert(ms_make_team_seating(ids, ratings, 8, teams, 6, NULL, 0, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_ERROR_TEAM_TOO_LARGE);
+    assert(seating.ids == NULL && seating.len == 0);
+}
+
+static void test_intersections_table(void) {
+    uint32_t ids[4] = {1, 2, 3, 4};
+    int32_t ratings[4] = {0, 0, 0, 0};
+
+    MsIntersections intersections;
+    assert(ms_make_intersections_table(ids, ratings, 4, ids, 4, &intersections) == MS_OK);
+    assert(intersections.len == 6);
+    for (size_t i = 0; i < intersections.len; i++) {
+        assert(intersections.data[i * 3 + 2] == 2);
+    }
+    ms_intersections_free(&intersections);
+}
+
+static void test_errors(void) {
+    uint32_t ids[4] = {1, 2, 3, 4};
+    int32_t ratings[4] = {0, 0, 0, 0};
+    MsSeating seating;
+
+    assert(ms_make_swiss_seating(ids, ratings, 3, NULL, 0, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_ERROR_INVALID_PLAYERS);
+    assert(seating.ids == NULL && seating.len == 0);
+    assert(ms_make_swiss_seating(NULL, ratings, 4, NULL, 0, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_ERROR_NULL_POINTER);
+    assert(ms_make_swiss_seating(ids, ratings, 4, ids, 2, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_ERROR_INVALID_PREVIOUS_SEATINGS);
+    assert(ms_make_swiss_seating(ids, ratings, 4, NULL, 0, 42, 1, &seating) == MS_ERROR_INVALID_ARGUMENT);
+    assert(ms_make_swiss_seating(ids, ratings, 4, NULL, 0, MS_WIND_POLICY_FIXED, 1, NULL) ==
+           MS_ERROR_NULL_POINTER);
+}
+
+static void test_validate(void) {
+    uint32_t ids[4] = {1, 2, 3, 4};
+    int32_t ratings[4] = {0, 0, 0, 0};
+    uint32_t previous[6] = {1, 2, 3, 42, 1, 1};
+
+    MsDiagnostics diagnostics;
+    assert(ms_validate(ids, ratings, 4, previous, 6, &diagnostics) == MS_OK);
+    assert(diagnostics.len == 3);
+    assert(diagnostics.data[0].kind == MS_DIAGNOSTIC_UNKNOWN_PLAYER);
+    assert(diagnostics.data[0].table == 0 && diagnostics.data[0].id == 42);
+    assert(!diagnostics.data[0].fatal);
+    assert(diagnostics.data[1].kind == MS_DIAGNOSTIC_MALFORMED_TABLE);
+    assert(diagnostics.data[1].table == 1 && diagnostics.data[1].size == 2 && diagnostics.data[1].fatal);
+    assert(diagnostics.data[2].kind == MS_DIAGNOSTIC_SELF_PAIRING);
+    ms_diagnostics_free(&diagnostics);
+    assert(diagnostics.data == NULL && diagnostics.len == 0);
+
+    assert(ms_validate(ids, ratings, 4, previous, 4, &diagnostics) == MS_OK);
+    assert(diagnostics.len == 1);
+    ms_diagnostics_free(&diagnostics);
+
+    /* Seating accepts unknown players in previous seatings */
+    MsSeating seating;
+    assert(ms_make_swiss_seating(ids, ratings, 4, previous, 4, MS_WIND_POLICY_FIXED, 1, &seating) ==
+           MS_OK);
+    ms_seating_free(&seating);
+}
+
+int main(void) {
+    test_swiss_seating();
+    test_shuffled_and_interval_seating();
+    test_team_seating();
+    test_intersections_table();
+    test_errors();
+    test_validate();
+    printf("All C API tests passed\n");
+    return 0;
+}
@@ -0,0 +1,77 @@
+//! Run with: wasm-pack test --node --features wasm
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use mahjong_seatings_rust::wasm::{
+    js_make_intersections_table, js_make_interval_seating, js_make_shuffled_seating,
+    js_make_swiss_seating, js_make_team_seating, js_validate_input, JsWindPolicy,
+};
+use wasm_bindgen_test::*;
+
+fn make_players() -> (Vec<u32>, Vec<i32>) {
+    let ids: Vec<u32> = (1..=16).collect();
+    let ratings: Vec<i32> = ids.iter().map(|id| 1500 - *id as i32).collect();
+    (ids, ratings)
+}
+
+#[wasm_bindgen_test]
+fn test_make_swiss_seating() {
+    let (ids, ratings) = make_players();
+    let seating = js_make_swiss_seating(&ids, &ratings, &[], JsWindPolicy::Fixed, 12345).unwrap();
+
+    let mut seated = seating.ids();
+    seated.sort();
+    assert_eq!(seated, ids);
+    assert_eq!(seating.ratings().len(), 16);
+}
+
+#[wasm_bindgen_test]
+fn test_make_shuffled_seating() {
+    let (ids, ratings) = make_players();
+    let previous: Vec<u32> = (1..=16).collect();
+    let seating =
+        js_make_shuffled_seating(&ids, &ratings, &previous, 1, JsWindPolicy::Random, 12345)
+            .unwrap();
+    assert_eq!(seating.ids().len(), 16);
+}
+
+#[wasm_bindgen_test]
+fn test_make_interval_seating() {
+    let (ids, ratings) = make_players();
+    let seating = js_make_interval_seating(&ids, &ratings, 2, JsWindPolicy::Fixed, 12345).unwrap();
+    assert_eq!(seating.ids().len(), 16);
+}
+
+#[wasm_bindgen_test]
+fn test_make_team_seating() {
+    let (ids, ratings) = make_players();
+    let teams: Vec<u32> = ids.iter().flat_map(|id| [*id, (id - 1) / 4]).collect();
+    let seating =
+        js_make_team_seating(&ids, &ratings, &teams, &[], JsWindPolicy::Fixed, 12345).unwrap();
+    assert_eq!(seating.ids().len(), 16);
+
+    // Five players of team 0 can't be seated at four tables
+    let mut teams = teams;
+    teams[9] = 0;
+    assert!(js_make_team_seating(&ids, &ratings, &teams, &[], JsWindPolicy::Fixed, 12345).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_make_intersections_table() {
+    let ids = vec![1, 2, 3, 4];
+    let ratings = vec![1500, 1500, 1500, 1500];
+    let table = js_make_intersections_table(&ids, &ratings, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(table.len(), 6 * 3);
+    assert!(table.chunks(3).all(|item| item[2] == 2));
+}
+
+#[wasm_bindgen_test]
+fn test_validate_input() {
+    let ids = vec![1, 2, 3, 4];
+    let ratings = vec![1500, 1500, 1500, 1500];
+    // Unknown player in previous seatings is reported only by strict validation
+    assert!(js_make_swiss_seating(&ids, &ratings, &[1, 2, 3, 42], JsWindPolicy::Fixed, 1).is_ok());
+    assert_eq!(
+        js_validate_input(&ids, &ratings, &[1, 2, 3, 42]).unwrap(),
+        vec![String::from("previous table #0 has unknown player 42")]
+    );
+}